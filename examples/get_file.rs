@@ -1,5 +1,5 @@
 use dotenv::dotenv;
-use futures_util::{future::BoxFuture, stream::StreamExt};
+use futures_util::future::BoxFuture;
 use std::{
     env,
     path::{Path, PathBuf},
@@ -7,11 +7,9 @@ use std::{
 use tempfile::tempdir;
 use tgbot::{
     longpoll::LongPoll,
-    methods::GetFile,
     types::{Document, MessageData, Update, UpdateKind},
     Api, Config, UpdateHandler,
 };
-use tokio::{fs::File, io::AsyncWriteExt};
 
 #[derive(Clone)]
 struct Handler {
@@ -21,17 +19,11 @@ struct Handler {
 
 async fn handle_document(api: &Api, tmpdir: &Path, document: Document) {
     let path = tmpdir.join(document.file_name.clone().unwrap_or_else(|| String::from("unknown")));
-    let file = api.execute(GetFile::new(document.file_id.as_str())).await.unwrap();
-    let file_path = file.file_path.unwrap();
-    let mut stream = api.download_file(file_path).await.unwrap();
     println!("Name: {:?}", document.file_name);
     println!("Mime-Type: {:?}", document.mime_type);
     println!("Document size: {:?}", document.file_size);
-    let mut file = File::create(path).await.unwrap();
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.unwrap();
-        file.write_all(&chunk).await.unwrap();
-    }
+    let size = api.download_to_path(&document, path).await.unwrap();
+    println!("Downloaded: {} bytes", size);
 }
 
 impl UpdateHandler for Handler {
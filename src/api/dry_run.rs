@@ -0,0 +1,451 @@
+use crate::{
+    api::{ExecuteError, Transport},
+    request::{FormValue, Request, RequestBody},
+    types::{ChatId, Integer},
+};
+use futures_util::future::BoxFuture;
+use log::info;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::sleep;
+
+const BOT_ID: Integer = 1;
+const DEFAULT_CAPACITY: usize = 1000;
+const DRY_RUN_INVITE_LINK: &str = "https://t.me/joinchat/dry_run";
+
+/// A transport which records requests instead of sending them
+///
+/// Each request is logged with `info` level and stored until it is taken using
+/// [`take_requests`](DryRunTransport::take_requests).
+/// Only the last 1000 requests are stored by default, see [`capacity`](DryRunTransport::capacity).
+/// State is shared between clones.
+///
+/// Responses are synthesized:
+///
+/// * methods which send or edit a message return a fake message with an incrementing ID
+/// * `sendMediaGroup` returns a fake message for each element of media group
+/// * `copyMessage` returns an incrementing message ID
+/// * `getMe` returns a fake bot
+/// * `getUpdates` returns an empty list after its long polling timeout,
+///   so [`LongPoll`](crate::longpoll::LongPoll) does not spin
+/// * `getChat`, `getChatAdministrators`, `getChatMember`, `getChatMemberCount`, `getFile`,
+///   `getWebhookInfo`, `getUserProfilePhotos`, `getMyCommands` and `getGameHighScores`
+///   return a fake or an empty result
+/// * `stopPoll` returns a fake closed poll
+/// * methods which create, edit or revoke an invite link return a fake link
+/// * other methods return `true`
+///
+/// Use [`response`](DryRunTransport::response) to return a predefined result for a method.
+/// It is required for methods which return neither `true` nor one of the results above,
+/// such as `getStickerSet`, otherwise the response can not be parsed.
+///
+/// # Example
+///
+/// ```
+/// # async fn dry_run() {
+/// use tgbot::{methods::SendMessage, Api, DryRunTransport};
+///
+/// let transport = DryRunTransport::new();
+/// let api = Api::new("token").unwrap().with_transport(transport.clone());
+/// let message = api.execute(SendMessage::new(1, "text")).await.unwrap();
+/// assert_eq!(message.id, 1);
+/// let requests = transport.take_requests();
+/// assert_eq!(requests[0].get_path(), "sendMessage");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DryRunTransport {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    requests: VecDeque<Request>,
+    capacity: usize,
+    responses: HashMap<String, JsonValue>,
+    last_message_id: Integer,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            requests: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            responses: HashMap::new(),
+            last_message_id: 0,
+        }
+    }
+}
+
+impl DryRunTransport {
+    /// Creates a new transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of stored requests
+    ///
+    /// The oldest request is removed when the limit is reached, use 0 to log requests only
+    ///
+    /// Defaults to 1000
+    pub fn capacity(self, capacity: usize) -> Self {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.capacity = capacity;
+            let len = state.requests.len();
+            state.requests.drain(..len.saturating_sub(capacity));
+        }
+        self
+    }
+
+    /// Sets a result returned for a method
+    ///
+    /// # Arguments
+    ///
+    /// * method_name - Name of the API method
+    /// * result - Value of `result` field in response
+    pub fn response<N: Into<String>>(self, method_name: N, result: JsonValue) -> Self {
+        self.state.lock().unwrap().responses.insert(method_name.into(), result);
+        self
+    }
+
+    /// Returns recorded requests and clears the list
+    pub fn take_requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.drain(..).collect()
+    }
+
+    /// Returns a response and a delay before it
+    fn record(&self, request: Request) -> (JsonValue, Option<Duration>) {
+        let params = get_params(&request);
+        info!("Dry run: {} {}", request.get_path(), JsonValue::Object(params.clone()));
+        let mut state = self.state.lock().unwrap();
+        let path = request.get_path();
+        let mut delay = None;
+        let result = match state.responses.get(path) {
+            Some(result) => result.clone(),
+            None => match path {
+                "getMe" => json!({
+                    "id": BOT_ID,
+                    "is_bot": true,
+                    "first_name": "Dry Run",
+                    "username": "dry_run_bot",
+                    "can_join_groups": true,
+                    "can_read_all_group_messages": false,
+                    "supports_inline_queries": false
+                }),
+                "getUpdates" => {
+                    delay = params
+                        .get("timeout")
+                        .and_then(JsonValue::as_u64)
+                        .map(Duration::from_secs);
+                    json!([])
+                }
+                "getChat" => fake_chat(request.get_chat_id().as_ref()),
+                "getChatAdministrators" => json!([{"status": "creator", "is_anonymous": false, "user": fake_bot()}]),
+                "getChatMember" => json!({
+                    "status": "member",
+                    "user": {
+                        "id": params.get("user_id").and_then(JsonValue::as_i64).unwrap_or_default(),
+                        "is_bot": false,
+                        "first_name": "Dry Run"
+                    }
+                }),
+                "getChatMemberCount" => json!(1),
+                "getFile" => {
+                    let file_id = params.get("file_id").cloned().unwrap_or_else(|| json!(""));
+                    json!({"file_id": file_id, "file_unique_id": file_id})
+                }
+                "getWebhookInfo" => json!({"url": "", "has_custom_certificate": false, "pending_update_count": 0}),
+                "getUserProfilePhotos" => json!({"total_count": 0, "photos": []}),
+                "getMyCommands" | "getGameHighScores" => json!([]),
+                "stopPoll" => json!({
+                    "type": "regular",
+                    "id": "0",
+                    "question": "Dry Run",
+                    "options": [],
+                    "total_voter_count": 0,
+                    "is_closed": true,
+                    "is_anonymous": true,
+                    "allows_multiple_answers": false
+                }),
+                "exportChatInviteLink" => json!(DRY_RUN_INVITE_LINK),
+                "createChatInviteLink" | "editChatInviteLink" | "revokeChatInviteLink" => {
+                    let mut link = json!({
+                        "invite_link": params.get("invite_link").cloned().unwrap_or_else(|| json!(DRY_RUN_INVITE_LINK)),
+                        "creator": fake_bot(),
+                        "is_primary": false,
+                        "is_revoked": path == "revokeChatInviteLink"
+                    });
+                    for key in &["expire_date", "member_limit"] {
+                        if let Some(value) = params.get(*key) {
+                            link[key] = value.clone();
+                        }
+                    }
+                    link
+                }
+                "copyMessage" => json!({"message_id": state.next_message_id()}),
+                "sendMediaGroup" => {
+                    let count = match params.get("media") {
+                        Some(JsonValue::Array(media)) => media.len(),
+                        _ => 0,
+                    };
+                    let chat_id = request.get_chat_id();
+                    JsonValue::Array(
+                        (0..count)
+                            .map(|_| fake_message(state.next_message_id(), chat_id.as_ref(), &params))
+                            .collect(),
+                    )
+                }
+                _ if returns_message(path) => match request.get_chat_id() {
+                    Some(chat_id) => {
+                        let message_id = match params.get("message_id") {
+                            Some(JsonValue::Number(message_id)) if path != "forwardMessage" => {
+                                message_id.as_i64().unwrap_or_default()
+                            }
+                            _ => state.next_message_id(),
+                        };
+                        fake_message(message_id, Some(&chat_id), &params)
+                    }
+                    // inline messages
+                    None => JsonValue::Bool(true),
+                },
+                _ => JsonValue::Bool(true),
+            },
+        };
+        if state.capacity > 0 {
+            if state.requests.len() >= state.capacity {
+                state.requests.pop_front();
+            }
+            state.requests.push_back(request);
+        }
+        (json!({"ok": true, "result": result}), delay)
+    }
+}
+
+impl State {
+    fn next_message_id(&mut self) -> Integer {
+        self.last_message_id += 1;
+        self.last_message_id
+    }
+}
+
+impl Transport for DryRunTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>> {
+        let (result, delay) = self.record(request);
+        Box::pin(async move {
+            if let Some(delay) = delay {
+                sleep(delay).await;
+            }
+            Ok(result)
+        })
+    }
+}
+
+fn returns_message(path: &str) -> bool {
+    (path.starts_with("send") && path != "sendChatAction")
+        || path.starts_with("editMessage")
+        || path == "forwardMessage"
+        || path == "stopMessageLiveLocation"
+        || path == "setGameScore"
+}
+
+/// Returns parameters of a request
+///
+/// Form fields are parsed as JSON when possible, files are omitted
+fn get_params(request: &Request) -> JsonMap<String, JsonValue> {
+    match request.get_body() {
+        RequestBody::Json(_) => match request.get_body().get_json() {
+            Some(JsonValue::Object(params)) => params,
+            _ => JsonMap::new(),
+        },
+        RequestBody::Form(form) => form
+            .iter_fields()
+            .filter_map(|(name, value)| match value {
+                FormValue::Text(text) => Some((
+                    String::from(name),
+                    serde_json::from_str(text).unwrap_or_else(|_| JsonValue::String(text.clone())),
+                )),
+                FormValue::File(_) => None,
+            })
+            .collect(),
+        RequestBody::Empty => JsonMap::new(),
+    }
+}
+
+fn fake_bot() -> JsonValue {
+    json!({"id": BOT_ID, "is_bot": true, "first_name": "Dry Run", "username": "dry_run_bot"})
+}
+
+fn fake_chat(chat_id: Option<&ChatId>) -> JsonValue {
+    match chat_id {
+        Some(ChatId::Id(id)) if *id > 0 => json!({"id": id, "type": "private", "first_name": "Dry Run"}),
+        Some(ChatId::Id(id)) => json!({"id": id, "type": "supergroup", "title": "Dry Run"}),
+        Some(ChatId::Username(username)) => json!({
+            "id": 0,
+            "type": "channel",
+            "title": "Dry Run",
+            "username": username.trim_start_matches('@')
+        }),
+        None => json!({"id": 0, "type": "supergroup", "title": "Dry Run"}),
+    }
+}
+
+fn fake_message(message_id: Integer, chat_id: Option<&ChatId>, params: &JsonMap<String, JsonValue>) -> JsonValue {
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default();
+    let mut message = json!({
+        "message_id": message_id,
+        "date": date,
+        "chat": fake_chat(chat_id),
+        "from": fake_bot()
+    });
+    if let Some(JsonValue::String(text)) = params.get("text") {
+        message["text"] = JsonValue::String(text.clone());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::Api,
+        methods::{
+            CopyMessage, CreateChatInviteLink, EditMessageText, GetChat, GetChatAdministrators, GetChatMember, GetFile,
+            GetMe, GetUpdates, GetWebhookInfo, RevokeChatInviteLink, SendChatAction, SendMediaGroup, SendMessage,
+            SendPhoto, StopPoll,
+        },
+        types::{
+            ChatAction, EditMessageResult, InputFile, InputMediaPhoto, MediaGroup, MessageData, MessageKind, Poll,
+        },
+    };
+
+    #[tokio::test]
+    async fn dry_run() {
+        let transport = DryRunTransport::new().response("getChatMemberCount", json!(5));
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+
+        let message = api.execute(SendMessage::new(1, "text")).await.unwrap();
+        assert_eq!(message.id, 1);
+        assert!(matches!(message.kind, MessageKind::Private { ref chat, .. } if chat.id == 1));
+        assert!(matches!(message.data, MessageData::Text(ref text) if text.data == "text"));
+
+        let message = api
+            .execute(SendPhoto::new("@channel", InputFile::file_id("photo-id")))
+            .await
+            .unwrap();
+        assert_eq!(message.id, 2);
+        assert!(
+            matches!(message.kind, MessageKind::Channel { ref chat, .. } if chat.username.as_deref() == Some("channel"))
+        );
+
+        let message = match api.execute(EditMessageText::new(-1, 1, "edited")).await.unwrap() {
+            EditMessageResult::Message(message) => message,
+            EditMessageResult::Bool(_) => panic!("Unexpected edit result"),
+        };
+        assert_eq!(message.id, 1);
+        assert!(matches!(message.kind, MessageKind::Supergroup { ref chat, .. } if chat.id == -1));
+
+        let media = MediaGroup::default()
+            .add_item(InputFile::file_id("photo-1"), InputMediaPhoto::default())
+            .add_item(InputFile::file_id("photo-2"), InputMediaPhoto::default());
+        let messages = api.execute(SendMediaGroup::new(1, media).unwrap()).await.unwrap();
+        assert_eq!(messages.iter().map(|x| x.id).collect::<Vec<_>>(), vec![3, 4]);
+
+        let message_id = api.execute(CopyMessage::new(1, 2, 3)).await.unwrap();
+        assert_eq!(message_id.message_id, 5);
+
+        assert!(api.execute(SendChatAction::new(1, ChatAction::Typing)).await.unwrap());
+        assert_eq!(api.execute(GetMe).await.unwrap().username, "dry_run_bot");
+        assert_eq!(
+            api.execute(crate::methods::GetChatMemberCount::new(1)).await.unwrap(),
+            5
+        );
+
+        let requests = transport.take_requests();
+        assert_eq!(
+            requests.iter().map(|x| x.get_path()).collect::<Vec<_>>(),
+            vec![
+                "sendMessage",
+                "sendPhoto",
+                "editMessageText",
+                "sendMediaGroup",
+                "copyMessage",
+                "sendChatAction",
+                "getMe",
+                "getChatMemberCount"
+            ]
+        );
+        assert_eq!(
+            requests[0].get_body().get_json().unwrap(),
+            json!({"chat_id": 1, "text": "text"})
+        );
+        assert!(transport.take_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_methods() {
+        let api = Api::new("token").unwrap().with_transport(DryRunTransport::new());
+        assert_eq!(api.execute(GetChat::new(1)).await.unwrap().get_id(), 1);
+        let admins = api.execute(GetChatAdministrators::new(-1)).await.unwrap();
+        assert_eq!(admins[0].get_user().id, BOT_ID);
+        assert_eq!(api.execute(GetChatMember::new(-1, 2)).await.unwrap().get_user().id, 2);
+        assert_eq!(api.execute(GetFile::new("file-id")).await.unwrap().file_id, "file-id");
+        assert!(api.execute(GetWebhookInfo).await.unwrap().url.is_empty());
+        assert!(matches!(
+            api.execute(StopPoll::new(1, 1)).await.unwrap(),
+            Poll::Regular(ref poll) if poll.is_closed
+        ));
+        let link = api
+            .execute(CreateChatInviteLink::new(-1).member_limit(5))
+            .await
+            .unwrap();
+        assert_eq!(link.invite_link, DRY_RUN_INVITE_LINK);
+        assert_eq!(link.member_limit, Some(5));
+        let link = api
+            .execute(RevokeChatInviteLink::new(-1, "https://t.me/joinchat/link"))
+            .await
+            .unwrap();
+        assert_eq!(link.invite_link, "https://t.me/joinchat/link");
+        assert!(link.is_revoked);
+    }
+
+    #[tokio::test]
+    async fn capacity() {
+        let transport = DryRunTransport::new();
+        assert_eq!(transport.state.lock().unwrap().capacity, DEFAULT_CAPACITY);
+        let transport = transport.capacity(2);
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        for chat_id in 1..=3 {
+            api.execute(SendMessage::new(chat_id, "text")).await.unwrap();
+        }
+        let requests = transport.take_requests();
+        let chat_ids = requests
+            .iter()
+            .map(|x| x.get_body().get_json().unwrap()["chat_id"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(chat_ids, vec![json!(2), json!(3)]);
+
+        let transport = transport.capacity(0);
+        api.execute(SendMessage::new(1, "text")).await.unwrap();
+        assert!(transport.take_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_updates_timeout() {
+        let api = Api::new("token").unwrap().with_transport(DryRunTransport::new());
+        let start = std::time::Instant::now();
+        let updates = api
+            .execute(GetUpdates::default().timeout(Duration::from_secs(1)))
+            .await
+            .unwrap();
+        assert!(updates.is_empty());
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{api::ExecuteError, request::Request};
+use serde_json::Value as JsonValue;
+use std::{error::Error as StdError, fmt, time::Duration};
+
+/// Intercepts methods executed by [`Api`](crate::Api)
+///
+/// Use it to log, meter or audit outgoing requests.
+/// Interceptors are called in the order they were added to `Api`.
+pub trait Interceptor: Send + Sync {
+    /// Called before a request is sent
+    ///
+    /// Return an error in order to cancel the request,
+    /// it will be returned from `Api::execute` as [`ExecuteError::Interceptor`]
+    ///
+    /// # Arguments
+    ///
+    /// * request - A request to send
+    fn before_request(&self, request: &Request) -> Result<(), InterceptorError> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called when a request is completed
+    ///
+    /// Not called when the request has been cancelled by an interceptor
+    ///
+    /// # Arguments
+    ///
+    /// * method_name - Name of the API method
+    /// * result - Value of `result` field in response or an error
+    /// * latency - Time spent to execute the method including retries
+    fn after_response(&self, method_name: &str, result: Result<&JsonValue, &ExecuteError>, latency: Duration) {
+        let _ = (method_name, result, latency);
+    }
+}
+
+/// An error returned by [`Interceptor`] in order to cancel a request
+#[derive(Clone, Debug)]
+pub struct InterceptorError {
+    reason: String,
+}
+
+impl InterceptorError {
+    /// Creates a new error with a given reason
+    pub fn new<S: Into<String>>(reason: S) -> Self {
+        Self { reason: reason.into() }
+    }
+
+    /// Reason why the request has been cancelled
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl StdError for InterceptorError {}
+
+impl fmt::Display for InterceptorError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "request cancelled by interceptor: {}", self.reason)
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{request::Request, types::ChatId};
+use std::{
+    cmp::max,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::{sleep_until, Instant};
+
+const MAX_IDLE_CHATS: usize = 1024;
+
+/// Limits a rate of outgoing messages according to Telegram limits
+///
+/// Only methods which send or edit messages are limited.
+/// Limiter state is shared between clones,
+/// so the same limiter can be used by several [`Api`](crate::Api) instances.
+///
+/// When Telegram replies with `retry_after` parameter,
+/// all requests are paused for the given period, including requests which are not limited.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    global_rate: Rate,
+    private_chat_rate: Rate,
+    group_chat_rate: Rate,
+    state: Arc<Mutex<State>>,
+}
+
+impl RateLimiter {
+    /// Creates a new limiter with default limits
+    ///
+    /// * 30 messages per second in total
+    /// * 1 message per second to the same private chat
+    /// * 20 messages per minute to the same group or channel
+    pub fn new() -> Self {
+        Self {
+            global_rate: Rate::new(30, Duration::from_secs(1)),
+            private_chat_rate: Rate::new(1, Duration::from_secs(1)),
+            group_chat_rate: Rate::new(20, Duration::from_secs(60)),
+            state: Default::default(),
+        }
+    }
+
+    /// Sets a maximum number of messages sent to all chats during a period
+    pub fn global_limit(mut self, count: u32, period: Duration) -> Self {
+        self.global_rate = Rate::new(count, period);
+        self
+    }
+
+    /// Sets a maximum number of messages sent to the same private chat during a period
+    pub fn private_chat_limit(mut self, count: u32, period: Duration) -> Self {
+        self.private_chat_rate = Rate::new(count, period);
+        self
+    }
+
+    /// Sets a maximum number of messages sent to the same group or channel during a period
+    pub fn group_chat_limit(mut self, count: u32, period: Duration) -> Self {
+        self.group_chat_rate = Rate::new(count, period);
+        self
+    }
+
+    /// Waits until the request can be sent
+    pub(crate) async fn wait(&self, request: &Request) {
+        if !is_limited(request.get_path()) {
+            loop {
+                let paused_until = self.state.lock().unwrap().paused_until;
+                if paused_until <= Instant::now() {
+                    return;
+                }
+                sleep_until(paused_until).await;
+            }
+        }
+        let chat_id = request.get_chat_id();
+        let chat_at = {
+            let mut state = self.state.lock().unwrap();
+            let now = max(Instant::now(), state.paused_until);
+            match chat_id {
+                Some(chat_id) => {
+                    let rate = match chat_id {
+                        ChatId::Id(id) if id > 0 => self.private_chat_rate,
+                        _ => self.group_chat_rate,
+                    };
+                    state.prune_chats(now);
+                    state
+                        .chats
+                        .entry(chat_id)
+                        .or_insert_with(|| Bucket::new(now))
+                        .reserve(rate, now)
+                }
+                None => now,
+            }
+        };
+        sleep_until(chat_at).await;
+        loop {
+            let at = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                if state.paused_until > now {
+                    Err(state.paused_until)
+                } else {
+                    Ok(state.global.reserve(self.global_rate, now))
+                }
+            };
+            match at {
+                Ok(at) => {
+                    sleep_until(at).await;
+                    break;
+                }
+                Err(paused_until) => sleep_until(paused_until).await,
+            }
+        }
+    }
+
+    /// Pauses all requests
+    pub(crate) fn pause(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.paused_until = max(state.paused_until, Instant::now() + duration);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Methods which edit a message
+///
+/// Other `edit*` methods such as `editChatInviteLink` do not count towards message limits
+const EDIT_MESSAGE_METHODS: &[&str] = &[
+    "editMessageCaption",
+    "editMessageLiveLocation",
+    "editMessageMedia",
+    "editMessageReplyMarkup",
+    "editMessageText",
+];
+
+fn is_limited(path: &str) -> bool {
+    (path.starts_with("send") && path != "sendChatAction")
+        || EDIT_MESSAGE_METHODS.contains(&path)
+        || path.starts_with("stop")
+        || path == "forwardMessage"
+        || path == "copyMessage"
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Rate {
+    interval: Duration,
+    tolerance: Duration,
+}
+
+impl Rate {
+    fn new(count: u32, period: Duration) -> Self {
+        let interval = period / max(count, 1);
+        Self {
+            interval,
+            tolerance: period - interval,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    global: Bucket,
+    chats: HashMap<ChatId, Bucket>,
+    paused_until: Instant,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            global: Bucket::new(now),
+            chats: HashMap::new(),
+            paused_until: now,
+        }
+    }
+}
+
+impl State {
+    fn prune_chats(&mut self, now: Instant) {
+        if self.chats.len() > MAX_IDLE_CHATS {
+            self.chats.retain(|_, bucket| bucket.tat > now);
+        }
+    }
+}
+
+/// Generic cell rate algorithm state
+#[derive(Debug)]
+struct Bucket {
+    /// Theoretical arrival time of the next request
+    tat: Instant,
+}
+
+impl Bucket {
+    fn new(now: Instant) -> Self {
+        Self { tat: now }
+    }
+
+    /// Reserves a slot and returns an instant when it becomes available
+    fn reserve(&mut self, rate: Rate, now: Instant) -> Instant {
+        let tat = max(self.tat, now);
+        let at = match tat.checked_sub(rate.tolerance) {
+            Some(at) => max(at, now),
+            None => now,
+        };
+        self.tat = tat + rate.interval;
+        at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Request;
+    use serde_json::json;
+
+    #[test]
+    fn reserve() {
+        let now = Instant::now();
+
+        let rate = Rate::new(1, Duration::from_secs(1));
+        let mut bucket = Bucket::new(now);
+        assert_eq!(bucket.reserve(rate, now), now);
+        assert_eq!(bucket.reserve(rate, now), now + Duration::from_secs(1));
+        assert_eq!(bucket.reserve(rate, now), now + Duration::from_secs(2));
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(rate, later), later);
+
+        let rate = Rate::new(20, Duration::from_secs(60));
+        let mut bucket = Bucket::new(now);
+        for _ in 0..20 {
+            assert_eq!(bucket.reserve(rate, now), now);
+        }
+        assert_eq!(bucket.reserve(rate, now), now + Duration::from_secs(3));
+        assert_eq!(bucket.reserve(rate, now), now + Duration::from_secs(6));
+    }
+
+    #[test]
+    fn limited_methods() {
+        assert!(is_limited("sendMessage"));
+        assert!(is_limited("sendPhoto"));
+        assert!(is_limited("editMessageText"));
+        assert!(is_limited("stopPoll"));
+        assert!(is_limited("forwardMessage"));
+        assert!(is_limited("copyMessage"));
+        assert!(!is_limited("sendChatAction"));
+        assert!(!is_limited("editChatInviteLink"));
+        assert!(!is_limited("getUpdates"));
+        assert!(!is_limited("getMe"));
+    }
+
+    #[tokio::test]
+    async fn wait() {
+        let limiter = RateLimiter::new()
+            .global_limit(2, Duration::from_millis(100))
+            .private_chat_limit(1, Duration::from_millis(100));
+        let shared = limiter.clone();
+
+        let request = Request::json("sendMessage", json!({"chat_id": 1, "text": "test"}));
+        let start = Instant::now();
+        limiter.wait(&request).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        shared.wait(&request).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let request = Request::json("getUpdates", json!({}));
+        let start = Instant::now();
+        for _ in 0..10 {
+            limiter.wait(&request).await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        let request = Request::json("sendMessage", json!({"chat_id": 2, "text": "test"}));
+        let start = Instant::now();
+        limiter.pause(Duration::from_millis(100));
+        shared.wait(&request).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let request = Request::json("getMe", json!({}));
+        let start = Instant::now();
+        limiter.pause(Duration::from_millis(100));
+        shared.wait(&request).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
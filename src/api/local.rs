@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+/// Settings of a local Bot API server
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LocalServer {
+    paths: Vec<(PathBuf, PathBuf)>,
+}
+
+impl LocalServer {
+    pub(crate) fn map_path(&mut self, server_path: PathBuf, local_path: PathBuf) {
+        self.paths.push((server_path, local_path));
+    }
+
+    /// Returns a path to read a file from or `None` when file must be downloaded over HTTP
+    ///
+    /// Local server returns absolute paths in file system of the server
+    pub(crate) fn resolve(&self, file_path: &str) -> Option<PathBuf> {
+        let file_path = Path::new(file_path);
+        if !file_path.is_absolute() {
+            return None;
+        }
+        for (server_path, local_path) in &self.paths {
+            if let Ok(relative_path) = file_path.strip_prefix(server_path) {
+                return Some(local_path.join(relative_path));
+            }
+        }
+        Some(file_path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let mut local_server = LocalServer::default();
+        assert!(local_server.resolve("documents/file_1.txt").is_none());
+        assert_eq!(
+            local_server.resolve("/var/lib/telegram-bot-api/documents/file_1.txt"),
+            Some(PathBuf::from("/var/lib/telegram-bot-api/documents/file_1.txt"))
+        );
+
+        local_server.map_path(
+            PathBuf::from("/var/lib/telegram-bot-api"),
+            PathBuf::from("/mnt/telegram-bot-api"),
+        );
+        assert_eq!(
+            local_server.resolve("/var/lib/telegram-bot-api/documents/file_1.txt"),
+            Some(PathBuf::from("/mnt/telegram-bot-api/documents/file_1.txt"))
+        );
+        assert_eq!(
+            local_server.resolve("/tmp/documents/file_1.txt"),
+            Some(PathBuf::from("/tmp/documents/file_1.txt"))
+        );
+    }
+}
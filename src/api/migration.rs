@@ -0,0 +1,50 @@
+use crate::types::{ChatId, Integer};
+use serde_json::Value as JsonValue;
+use std::{fmt, sync::Arc};
+
+/// A hook called when a group has been migrated to a supergroup
+#[derive(Clone)]
+pub(crate) struct ChatMigrationHook(Arc<dyn Fn(ChatId, Integer) + Send + Sync>);
+
+impl ChatMigrationHook {
+    pub(crate) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(ChatId, Integer) + Send + Sync + 'static,
+    {
+        Self(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, old_chat_id: ChatId, new_chat_id: Integer) {
+        (self.0)(old_chat_id, new_chat_id)
+    }
+
+    /// Calls the hook for migration service messages in a result of `getUpdates` method
+    ///
+    /// Such updates are received both from the group and from the supergroup,
+    /// so the hook may be called twice for the same migration.
+    pub(crate) fn call_for_updates(&self, updates: &JsonValue) {
+        let messages = updates
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|update| update.get("message"));
+        for message in messages {
+            let chat_id = match message.pointer("/chat/id").and_then(JsonValue::as_i64) {
+                Some(chat_id) => chat_id,
+                None => continue,
+            };
+            if let Some(new_chat_id) = message.get("migrate_to_chat_id").and_then(JsonValue::as_i64) {
+                self.call(ChatId::Id(chat_id), new_chat_id);
+            }
+            if let Some(old_chat_id) = message.get("migrate_from_chat_id").and_then(JsonValue::as_i64) {
+                self.call(ChatId::Id(old_chat_id), chat_id);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ChatMigrationHook {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "ChatMigrationHook")
+    }
+}
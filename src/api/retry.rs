@@ -0,0 +1,233 @@
+use crate::api::ExecuteError;
+use std::{
+    cmp::min,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A policy for retrying failed requests
+///
+/// A request is retried when:
+///
+/// * Telegram asks to wait using `retry_after` parameter (the delay is taken from the response)
+/// * server replied with 5xx status code
+/// * connection could not be established or has been reset
+/// * request has timed out and the method only reads data (`get*` methods)
+///
+/// Other methods are not retried after a timeout, since Telegram may have executed them already,
+/// e.g. a message would be sent twice.
+/// Errors caused by the request itself (4xx) are never retried.
+/// Requests with files uploaded from a reader can not be repeated and are sent only once.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a new policy
+    ///
+    /// # Arguments
+    ///
+    /// * max_attempts - Maximum number of attempts including the first one
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Delay before the first retry
+    ///
+    /// Delay is doubled after each attempt and a random jitter is added to it
+    ///
+    /// Defaults to 500 ms
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Maximum delay between attempts
+    ///
+    /// Does not limit a delay requested by Telegram using `retry_after` parameter
+    ///
+    /// Defaults to 30 seconds
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub(crate) fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns a delay before the next attempt or `None` when request should not be retried
+    ///
+    /// Attempts are numbered from 1
+    pub(crate) fn get_delay(&self, attempt: u32, method_name: &str, err: &ExecuteError) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        if let ExecuteError::Response(err) = err {
+            if let Some(retry_after) = err.retry_after() {
+                return Some(Duration::from_secs(retry_after as u64));
+            }
+        }
+        if !is_retryable(method_name, err) {
+            return None;
+        }
+        Some(get_backoff(self.initial_backoff, self.max_backoff, attempt))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+fn is_retryable(method_name: &str, err: &ExecuteError) -> bool {
+    match err {
+        // a request could not be sent when connection has not been established
+        ExecuteError::Reqwest(err) if err.is_timeout() && !err.is_connect() => is_read_only(method_name),
+        ExecuteError::Reqwest(err) => match err.status() {
+            Some(status) => status.is_server_error(),
+            None => err.is_connect() || err.is_request(),
+        },
+        ExecuteError::Response(err) => matches!(err.error_code(), Some(code) if code >= 500),
+        ExecuteError::Timeout(_) => is_read_only(method_name),
+        _ => false,
+    }
+}
+
+/// Whether a method can be executed several times without side effects
+fn is_read_only(method_name: &str) -> bool {
+    method_name.starts_with("get")
+}
+
+/// Returns a delay which is doubled after each attempt with a random jitter
+///
+/// Attempts are numbered from 1
+fn get_backoff(initial_backoff: Duration, max_backoff: Duration, attempt: u32) -> Duration {
+    let backoff = get_exponential_backoff(initial_backoff, max_backoff, attempt);
+    let half = backoff / 2;
+    half + jitter(half)
+}
+
+/// Returns a delay which is doubled after each attempt without a jitter
+///
+/// Attempts are numbered from 1
+pub(crate) fn get_exponential_backoff(initial_backoff: Duration, max_backoff: Duration, attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    min(initial_backoff.saturating_mul(factor), max_backoff)
+}
+
+/// Returns a random delay less than the given one
+pub(crate) fn jitter(max: Duration) -> Duration {
+    let max_nanos = max.as_nanos() as u64;
+    if max_nanos == 0 {
+        return max;
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.subsec_nanos())
+        .unwrap_or_default();
+    hasher.write_u32(now);
+    Duration::from_nanos(hasher.finish() % max_nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Response;
+
+    fn response_error(value: serde_json::Value) -> ExecuteError {
+        match serde_json::from_value::<Response<()>>(value).unwrap() {
+            Response::Error(err) => ExecuteError::Response(err),
+            Response::Success(_) => unreachable!(),
+        }
+    }
+
+    fn server_error() -> ExecuteError {
+        response_error(serde_json::json!({
+            "ok": false,
+            "description": "Internal Server Error",
+            "error_code": 500
+        }))
+    }
+
+    #[test]
+    fn retry_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.get_max_attempts(), 3);
+        assert_eq!(policy.initial_backoff, DEFAULT_INITIAL_BACKOFF);
+        assert_eq!(policy.max_backoff, DEFAULT_MAX_BACKOFF);
+
+        let policy = RetryPolicy::new(5)
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(3));
+        assert_eq!(policy.get_max_attempts(), 5);
+        assert_eq!(policy.initial_backoff, Duration::from_secs(1));
+        assert_eq!(policy.max_backoff, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn get_delay() {
+        let policy = RetryPolicy::new(4)
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(3));
+        let err = server_error();
+        let delay = policy.get_delay(1, "sendMessage", &err).unwrap();
+        assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_secs(1));
+        let delay = policy.get_delay(2, "sendMessage", &err).unwrap();
+        assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+        let delay = policy.get_delay(3, "sendMessage", &err).unwrap();
+        assert!(delay >= Duration::from_millis(1500) && delay <= Duration::from_secs(3));
+        assert!(policy.get_delay(4, "sendMessage", &err).is_none());
+
+        let err = response_error(serde_json::json!({
+            "ok": false,
+            "description": "Too Many Requests: retry after 10",
+            "error_code": 429,
+            "parameters": {"retry_after": 10}
+        }));
+        assert_eq!(policy.get_delay(1, "sendMessage", &err), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn get_delay_not_retryable() {
+        let policy = RetryPolicy::new(3);
+        let err = response_error(serde_json::json!({
+            "ok": false,
+            "description": "Bad Request: chat not found",
+            "error_code": 400
+        }));
+        assert!(policy.get_delay(1, "sendMessage", &err).is_none());
+
+        let err = ExecuteError::Json(serde_json::from_str::<serde_json::Value>("").unwrap_err());
+        assert!(policy.get_delay(1, "sendMessage", &err).is_none());
+    }
+
+    #[test]
+    fn get_delay_timeout() {
+        let policy = RetryPolicy::new(3);
+        let err = ExecuteError::Timeout(Duration::from_secs(1));
+        assert!(policy.get_delay(1, "getChat", &err).is_some());
+        assert!(policy.get_delay(1, "sendMessage", &err).is_none());
+    }
+
+    #[test]
+    fn jitter_bounds() {
+        assert_eq!(jitter(Duration::from_secs(0)), Duration::from_secs(0));
+        for _ in 0..10 {
+            assert!(jitter(Duration::from_millis(10)) < Duration::from_millis(10));
+        }
+    }
+}
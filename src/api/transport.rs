@@ -0,0 +1,116 @@
+use crate::{
+    api::{redact_token, ExecuteError},
+    request::{Request, RequestBody, RequestMethod},
+};
+use futures_util::future::BoxFuture;
+use log::debug;
+use reqwest::Client;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// A transport used by [`Api`](crate::Api) to send requests
+///
+/// Implement this trait in order to send requests using a custom HTTP stack,
+/// record requests or reply with predefined responses in tests.
+pub trait Transport: Send + Sync {
+    /// Sends a request
+    ///
+    /// Returns a response body as is, that is an object with `ok` field:
+    /// `{"ok": true, "result": ...}` or `{"ok": false, "description": ...}`
+    ///
+    /// # Arguments
+    ///
+    /// * request - A request to send
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>>;
+}
+
+/// Default transport based on reqwest
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+    host: String,
+    token: String,
+}
+
+impl ReqwestTransport {
+    /// Creates a new transport
+    ///
+    /// # Arguments
+    ///
+    /// * client - HTTP client
+    /// * host - API host
+    /// * token - Bot token
+    pub fn new<H, T>(client: Client, host: H, token: T) -> Self
+    where
+        H: Into<String>,
+        T: Into<String>,
+    {
+        Self {
+            client,
+            host: host.into(),
+            token: token.into(),
+        }
+    }
+
+    async fn send_request(&self, request: Request) -> Result<JsonValue, ExecuteError> {
+        self.send_request_inner(request).await.map_err(|err| match err {
+            ExecuteError::Reqwest(err) => ExecuteError::Reqwest(redact_token(err, &self.token)),
+            err => err,
+        })
+    }
+
+    async fn send_request_inner(&self, request: Request) -> Result<JsonValue, ExecuteError> {
+        let url = request.build_url(&self.host, &self.token);
+        let http_req = match request.get_method() {
+            RequestMethod::Get => {
+                debug!("Execute GET {}", request.get_path());
+                self.client.get(&url)
+            }
+            RequestMethod::Post => {
+                debug!("Execute POST: {}", request.get_path());
+                self.client.post(&url)
+            }
+        };
+        let rep = match request.into_body() {
+            RequestBody::Form(form) => {
+                let form = form.into_multipart().await?;
+                debug!("Sending multipart body: {:?}", form);
+                http_req.multipart(form)
+            }
+            RequestBody::Json(data) => {
+                let data = data?;
+                debug!("Sending JSON body: {:?}", data);
+                http_req.header("Content-Type", "application/json").body(data)
+            }
+            RequestBody::Empty => {
+                debug!("Sending empty body");
+                http_req
+            }
+        }
+        .send()
+        .await?;
+        // Telegram replies with JSON for most of errors,
+        // but a reverse proxy in front of it may reply with something else
+        let status_error = rep.error_for_status_ref().err();
+        match rep.json().await {
+            Ok(data) => Ok(data),
+            Err(err) => Err(status_error.unwrap_or(err).into()),
+        }
+    }
+}
+
+impl fmt::Debug for ReqwestTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReqwestTransport")
+            .field("client", &self.client)
+            .field("host", &self.host)
+            .field("token", &format_args!("..."))
+            .finish()
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>> {
+        Box::pin(self.send_request(request))
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    api::{
+        Api as AsyncApi, ApiError, Config, DownloadFileError, ExecuteError, Interceptor, SwitchServerError, Transport,
+    },
+    methods::Method,
+    types::FileReference,
+};
+use futures_util::{pin_mut, stream::TryStreamExt};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    io::{Result as IoResult, Write},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::AsyncWrite,
+    runtime::{Builder as RuntimeBuilder, Runtime},
+};
+
+/// Synchronous Telegram Bot API client
+///
+/// A wrapper around [`Api`](crate::Api) which runs requests on its own runtime,
+/// so all settings of [`Config`] are supported.
+///
+/// Do not use it inside of an asynchronous context, it will panic.
+///
+/// # Example
+///
+/// ```no_run
+/// use tgbot::{blocking::Api, methods::GetMe};
+///
+/// let api = Api::new("token").unwrap();
+/// let me = api.execute(GetMe).unwrap();
+/// println!("{:?}", me);
+/// ```
+#[derive(Clone)]
+pub struct Api {
+    inner: AsyncApi,
+    runtime: Arc<Runtime>,
+}
+
+impl Api {
+    /// Creates a new API instance with given configuration
+    pub fn new<C: Into<Config>>(config: C) -> Result<Self, ApiError> {
+        let runtime = RuntimeBuilder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ApiError::BuildRuntime)?;
+        Ok(Self {
+            inner: AsyncApi::new(config)?,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Replaces a transport used to execute methods
+    ///
+    /// See [`Api::with_transport`](crate::Api::with_transport)
+    pub fn with_transport<T>(mut self, transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
+        self.inner = self.inner.with_transport(transport);
+        self
+    }
+
+    /// Adds an interceptor
+    ///
+    /// See [`Api::with_interceptor`](crate::Api::with_interceptor)
+    pub fn with_interceptor<I>(mut self, interceptor: I) -> Self
+    where
+        I: Interceptor + 'static,
+    {
+        self.inner = self.inner.with_interceptor(interceptor);
+        self
+    }
+
+    /// Returns an asynchronous API instance
+    pub fn get_async(&self) -> &AsyncApi {
+        &self.inner
+    }
+
+    /// Returns a maximum size of a file which can be downloaded
+    pub fn get_max_download_size(&self) -> Option<u64> {
+        self.inner.get_max_download_size()
+    }
+
+    /// Downloads a file and writes it to a given writer
+    ///
+    /// Use `getFile` method in order to get a value for `file_path` argument
+    ///
+    /// Returns a number of written bytes
+    pub fn download_file<P, W>(&self, file_path: P, writer: &mut W) -> Result<u64, DownloadFileError>
+    where
+        P: AsRef<str>,
+        W: Write,
+    {
+        self.runtime.block_on(async {
+            let stream = self.inner.download_file(file_path).await?;
+            pin_mut!(stream);
+            let mut size = 0;
+            while let Some(chunk) = stream.try_next().await? {
+                writer.write_all(&chunk)?;
+                size += chunk.len() as u64;
+            }
+            writer.flush()?;
+            Ok(size)
+        })
+    }
+
+    /// Downloads a file and writes it to a given writer
+    ///
+    /// See [`Api::download`](crate::Api::download)
+    pub fn download<F, W>(&self, file: &F, writer: &mut W) -> Result<u64, DownloadFileError>
+    where
+        F: FileReference + ?Sized,
+        W: Write,
+    {
+        self.runtime
+            .block_on(self.inner.download(file, &mut SyncWriter(writer)))
+    }
+
+    /// Downloads a file and saves it to a given path
+    ///
+    /// See [`Api::download_to_path`](crate::Api::download_to_path)
+    pub fn download_to_path<F, P>(&self, file: &F, path: P) -> Result<u64, DownloadFileError>
+    where
+        F: FileReference + ?Sized,
+        P: AsRef<Path>,
+    {
+        self.runtime.block_on(self.inner.download_to_path(file, path))
+    }
+
+    /// Executes a method
+    ///
+    /// See [`Api::execute`](crate::Api::execute)
+    pub fn execute<M: Method>(&self, method: M) -> Result<M::Response, ExecuteError>
+    where
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        self.runtime.block_on(self.inner.execute(method))
+    }
+
+    /// Executes a method with a given timeout
+    ///
+    /// See [`Api::execute_with_timeout`](crate::Api::execute_with_timeout)
+    pub fn execute_with_timeout<M: Method>(
+        &self,
+        method: M,
+        request_timeout: Duration,
+    ) -> Result<M::Response, ExecuteError>
+    where
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        self.runtime
+            .block_on(self.inner.execute_with_timeout(method, request_timeout))
+    }
+
+    /// Moves the bot to another Bot API server
+    ///
+    /// See [`Api::switch_server`](crate::Api::switch_server)
+    pub fn switch_server<C: Into<Config>>(&self, config: C) -> Result<Api, SwitchServerError> {
+        Ok(Self {
+            inner: self.runtime.block_on(self.inner.switch_server(config))?,
+            runtime: self.runtime.clone(),
+        })
+    }
+}
+
+impl fmt::Debug for Api {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Api").field("inner", &self.inner).finish()
+    }
+}
+
+/// Writes data to a synchronous writer
+///
+/// Writes are blocking, which is fine for a runtime owned by a single thread
+struct SyncWriter<'a, W>(&'a mut W);
+
+impl<W: Write> AsyncWrite for SyncWriter<'_, W> {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.poll_flush(cx)
+    }
+}
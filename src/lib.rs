@@ -7,6 +7,10 @@ mod api;
 mod handler;
 mod request;
 
+/// Synchronous API client
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 /// Utilities to receive updates using long poll
 pub mod longpoll;
 
@@ -20,8 +24,12 @@ pub mod types;
 pub mod webhook;
 
 pub use self::{
-    api::{Api, ApiError, Config, DownloadFileError, ExecuteError, ParseProxyError},
+    api::{
+        Api, ApiError, Config, DownloadFileError, DryRunTransport, ExecuteError, Interceptor, InterceptorError,
+        ParseProxyError, RateLimiter, ReqwestTransport, RetryPolicy, SwitchServerError, Transport,
+    },
     handler::{SyncedUpdateHandler, UpdateHandler},
+    request::{Form, FormError, FormValue, Request, RequestBody, RequestMethod},
 };
 
 pub use mime;
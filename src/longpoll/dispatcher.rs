@@ -0,0 +1,203 @@
+use crate::{
+    handler::UpdateHandler,
+    types::{Integer, Update},
+};
+use futures_util::{
+    future::{join, ready, Join, Ready},
+    stream::{FuturesUnordered, StreamExt},
+};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// A key to order updates and an update ID
+type Tag = (Option<Integer>, Integer);
+
+/// Runs handlers concurrently
+///
+/// Updates from the same chat or user are handled one by one in the order they were received
+pub(super) struct Dispatcher<'a, H: UpdateHandler> {
+    handler: &'a H,
+    limit: usize,
+    running: FuturesUnordered<Join<H::Future, Ready<Tag>>>,
+    queues: HashMap<Integer, VecDeque<Update>>,
+    queued: usize,
+    pending: BTreeSet<Integer>,
+    first_dispatched: Option<Integer>,
+    last_dispatched: Option<Integer>,
+    handled: u64,
+}
+
+impl<'a, H: UpdateHandler> Dispatcher<'a, H> {
+    pub(super) fn new(handler: &'a H, limit: usize) -> Self {
+        Self {
+            handler,
+            limit: limit.max(1),
+            running: FuturesUnordered::new(),
+            queues: HashMap::new(),
+            queued: 0,
+            pending: BTreeSet::new(),
+            first_dispatched: None,
+            last_dispatched: None,
+            handled: 0,
+        }
+    }
+
+    /// Whether a new update can be accepted
+    pub(super) fn is_ready(&self) -> bool {
+        self.running.len() < self.limit && self.queued < self.limit
+    }
+
+    /// Whether there are no running handlers
+    pub(super) fn is_idle(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Returns an ID of the last update such that all updates before it have been handled
+    pub(super) fn get_offset(&self) -> Option<Integer> {
+        let first_dispatched = self.first_dispatched?;
+        let offset = match self.pending.iter().next() {
+            Some(update_id) => update_id - 1,
+            None => self.last_dispatched?,
+        };
+        if offset < first_dispatched {
+            None
+        } else {
+            Some(offset)
+        }
+    }
+
+    /// Returns an ID of the first update which has been dispatched but not handled yet
+    pub(super) fn get_first_pending(&self) -> Option<Integer> {
+        self.pending.iter().next().copied()
+    }
+
+    /// Returns a number of handled updates
+    pub(super) fn get_handled(&self) -> u64 {
+        self.handled
+    }
+
+    /// Returns a number of running and queued updates
+    pub(super) fn get_unhandled(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Starts a handler or puts an update to a queue when its chat is busy
+    pub(super) fn dispatch(&mut self, update: Update) {
+        self.pending.insert(update.id);
+        self.first_dispatched.get_or_insert(update.id);
+        self.last_dispatched = Some(self.last_dispatched.map_or(update.id, |x| x.max(update.id)));
+        let key = get_key(&update);
+        if let Some(key) = key {
+            if let Some(queue) = self.queues.get_mut(&key) {
+                queue.push_back(update);
+                self.queued += 1;
+                return;
+            }
+            self.queues.insert(key, VecDeque::new());
+        }
+        self.start(key, update);
+    }
+
+    /// Waits until a handler completes
+    ///
+    /// Returns an ID of the handled update or `None` when there are no running handlers
+    pub(super) async fn next(&mut self) -> Option<Integer> {
+        let ((), (key, update_id)) = self.running.next().await?;
+        self.pending.remove(&update_id);
+        self.handled += 1;
+        if let Some(key) = key {
+            match self.queues.get_mut(&key).and_then(|queue| queue.pop_front()) {
+                Some(update) => {
+                    self.queued -= 1;
+                    self.start(Some(key), update);
+                }
+                None => {
+                    self.queues.remove(&key);
+                }
+            }
+        }
+        Some(update_id)
+    }
+
+    fn start(&mut self, key: Option<Integer>, update: Update) {
+        let update_id = update.id;
+        self.running
+            .push(join(self.handler.handle(update), ready((key, update_id))));
+    }
+}
+
+fn get_key(update: &Update) -> Option<Integer> {
+    update.get_chat_id().or_else(|| update.get_user().map(|user| user.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+    use tokio::time::sleep;
+
+    struct Handler {
+        events: Arc<Mutex<Vec<(&'static str, Integer)>>>,
+    }
+
+    impl UpdateHandler for Handler {
+        type Future = BoxFuture<'static, ()>;
+
+        fn handle(&self, update: Update) -> Self::Future {
+            let events = self.events.clone();
+            Box::pin(async move {
+                events.lock().unwrap().push(("start", update.id));
+                // first update of a chat is the slowest one
+                sleep(Duration::from_millis(if update.id % 10 == 1 { 50 } else { 10 })).await;
+                events.lock().unwrap().push(("end", update.id));
+            })
+        }
+    }
+
+    fn create_update(update_id: Integer, chat_id: Integer) -> Update {
+        serde_json::from_value(serde_json::json!({
+            "update_id": update_id,
+            "message": {
+                "message_id": update_id,
+                "date": 0,
+                "from": {"id": chat_id, "is_bot": false, "first_name": "test"},
+                "chat": {"id": chat_id, "type": "private", "first_name": "test"},
+                "text": "test"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dispatch() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handler = Handler { events: events.clone() };
+        let mut dispatcher = Dispatcher::new(&handler, 2);
+        assert!(dispatcher.is_idle());
+        assert!(dispatcher.get_offset().is_none());
+        dispatcher.dispatch(create_update(11, 1));
+        dispatcher.dispatch(create_update(12, 1));
+        assert!(dispatcher.is_ready());
+        dispatcher.dispatch(create_update(22, 2));
+        assert!(!dispatcher.is_ready());
+        assert_eq!(dispatcher.get_unhandled(), 3);
+        let mut handled = Vec::new();
+        let mut offsets = Vec::new();
+        while let Some(update_id) = dispatcher.next().await {
+            handled.push(update_id);
+            offsets.push(dispatcher.get_offset());
+        }
+        assert!(dispatcher.is_idle());
+        assert_eq!(handled, vec![22, 11, 12]);
+        assert_eq!(offsets, vec![None, Some(11), Some(22)]);
+        assert_eq!(dispatcher.get_handled(), 3);
+        assert_eq!(dispatcher.get_unhandled(), 0);
+        let events = events.lock().unwrap();
+        let position = |event| events.iter().position(|x| *x == event).unwrap();
+        assert!(position(("start", 22)) < position(("end", 11)));
+        assert!(position(("end", 11)) < position(("start", 12)));
+    }
+}
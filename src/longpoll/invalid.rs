@@ -0,0 +1,50 @@
+use crate::types::Integer;
+use serde_json::{Error as JsonError, Value as JsonValue};
+use std::{fmt, sync::Arc};
+
+/// An update which could not be parsed
+///
+/// Such updates are skipped and the offset is advanced past them,
+/// so they are not received again.
+/// Updates of unknown kinds are not invalid,
+/// they are received as [`UpdateKind::Unknown`](crate::types::UpdateKind::Unknown).
+#[derive(Debug)]
+pub struct InvalidUpdate {
+    /// ID of the update
+    pub update_id: Integer,
+    /// Update as received from Telegram
+    pub data: JsonValue,
+    /// Parse error
+    pub error: JsonError,
+}
+
+/// A hook called when an update could not be parsed
+#[derive(Clone)]
+pub(super) struct InvalidUpdateHook(Arc<dyn Fn(InvalidUpdate) + Send + Sync>);
+
+impl InvalidUpdateHook {
+    pub(super) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(InvalidUpdate) + Send + Sync + 'static,
+    {
+        Self(Arc::new(hook))
+    }
+
+    pub(super) fn call(&self, update: InvalidUpdate) {
+        (self.0)(update)
+    }
+}
+
+impl fmt::Debug for InvalidUpdateHook {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "InvalidUpdateHook")
+    }
+}
+
+impl PartialEq for InvalidUpdateHook {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for InvalidUpdateHook {}
@@ -0,0 +1,853 @@
+use crate::{
+    api::Api,
+    handler::UpdateHandler,
+    methods::GetUpdates,
+    types::{AllowedUpdate, Integer},
+};
+use futures_util::{
+    future::{select, Either},
+    pin_mut,
+    stream::StreamExt,
+};
+use log::{error, warn};
+use std::{
+    cmp::max,
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        watch,
+    },
+    time::timeout,
+};
+
+mod dispatcher;
+mod invalid;
+mod offset;
+mod stream;
+
+use self::{dispatcher::Dispatcher, invalid::InvalidUpdateHook};
+
+pub use self::{
+    invalid::InvalidUpdate,
+    offset::{FileOffsetStore, MemoryOffsetStore, OffsetStore, OffsetStoreError},
+    stream::LongPollStream,
+};
+
+const DEFAULT_LIMIT: Integer = 100;
+const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_ERROR_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_ERROR_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_CONCURRENCY_LIMIT: usize = 1;
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Receive incoming updates using long polling
+pub struct LongPoll<H> {
+    api: Api,
+    handler: Box<H>,
+    options: LongPollOptions,
+    offset_store: Option<Box<dyn OffsetStore>>,
+    status: Arc<Mutex<LongPollStatus>>,
+    sender: Sender<()>,
+    receiver: Receiver<()>,
+}
+
+impl<H> LongPoll<H> {
+    /// Creates a new LongPoll
+    ///
+    /// # Arguments
+    ///
+    /// * api - Telegram Bot API Client
+    /// * handler - Updates Handler
+    pub fn new(api: Api, handler: H) -> Self {
+        let (sender, receiver) = channel(1);
+        Self {
+            api,
+            handler: Box::new(handler),
+            options: LongPollOptions::default(),
+            offset_store: None,
+            status: Default::default(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// Set poll options
+    pub fn options(mut self, options: LongPollOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set a store to resume polling from the last handled update after restart
+    ///
+    /// Updates handled before a crash may be delivered again,
+    /// but none of the updates are lost:
+    /// neither the store nor Telegram confirm an update until all updates before it have been handled,
+    /// whatever the concurrency limit is
+    pub fn offset_store<S>(mut self, offset_store: S) -> Self
+    where
+        S: OffsetStore + 'static,
+    {
+        self.offset_store = Some(Box::new(offset_store));
+        self
+    }
+}
+
+impl<H> LongPoll<H>
+where
+    H: UpdateHandler,
+{
+    /// Returns a long poll handle
+    pub fn get_handle(&self) -> LongPollHandle {
+        LongPollHandle {
+            sender: self.sender.clone(),
+            status: self.status.clone(),
+        }
+    }
+
+    /// Start polling loop
+    ///
+    /// Returns a report when the loop has been stopped using [`LongPollHandle::shutdown`]
+    pub async fn run(self) -> LongPollReport {
+        let mut options = self.options;
+        let offset_store = self.offset_store.as_deref();
+        let mut committed = None;
+        if let Some(offset_store) = offset_store {
+            match offset_store.load().await {
+                Ok(Some(update_id)) => {
+                    options.offset = max(options.offset, update_id);
+                    committed = Some(update_id);
+                }
+                Ok(None) => {}
+                Err(err) => error!("An error has occurred while loading offset: {}", err),
+            }
+        }
+        let concurrency_limit = options.concurrency_limit;
+        let shutdown_timeout = options.shutdown_timeout;
+        let (pending_sender, pending_receiver) = watch::channel(None);
+        let mut s = LongPollStream::with_status(self.api.clone(), options, self.status.clone(), Some(pending_receiver));
+        let mut receiver = self.receiver;
+        let shutdown = receiver.recv();
+        pin_mut!(shutdown);
+        let mut dispatcher = Dispatcher::new(&*self.handler, concurrency_limit);
+        loop {
+            let handled_offset = dispatcher.get_offset();
+            {
+                let mut status = self.status.lock().unwrap();
+                status.handled = dispatcher.get_handled();
+                status.handled_offset = handled_offset.or(status.handled_offset);
+            }
+            commit_offset(offset_store, &mut committed, handled_offset).await;
+            let first_pending = dispatcher.get_first_pending();
+            if *pending_sender.borrow() != first_pending {
+                let _ = pending_sender.send(first_pending);
+            }
+            let update = if dispatcher.is_idle() {
+                match select(&mut shutdown, s.next()).await {
+                    Either::Left(_) => break,
+                    Either::Right((update, _)) => update,
+                }
+            } else if dispatcher.is_ready() {
+                let handled = dispatcher.next();
+                pin_mut!(handled);
+                match select(&mut shutdown, select(s.next(), handled)).await {
+                    Either::Left(_) => break,
+                    Either::Right((Either::Left((update, _)), _)) => update,
+                    Either::Right((Either::Right(_), _)) => continue,
+                }
+            } else {
+                let handled = dispatcher.next();
+                pin_mut!(handled);
+                match select(&mut shutdown, handled).await {
+                    Either::Left(_) => break,
+                    Either::Right(_) => continue,
+                }
+            };
+            match update {
+                Some(update) => dispatcher.dispatch(update),
+                None => break,
+            }
+        }
+        // Cancel a pending request, updates received but not dispatched yet will be delivered again
+        drop(s);
+        let drain = async { while dispatcher.next().await.is_some() {} };
+        if timeout(shutdown_timeout, drain).await.is_err() {
+            warn!(
+                "Shutdown timeout has elapsed, {} updates have not been handled",
+                dispatcher.get_unhandled()
+            );
+        }
+        let last_update_id = dispatcher.get_offset();
+        {
+            let mut status = self.status.lock().unwrap();
+            status.handled = dispatcher.get_handled();
+            status.handled_offset = last_update_id.or(status.handled_offset);
+        }
+        commit_offset(offset_store, &mut committed, last_update_id).await;
+        let offset_confirmed = match last_update_id {
+            Some(update_id) => {
+                let method = GetUpdates::default()
+                    .offset(update_id + 1)
+                    .limit(1)
+                    .timeout(Duration::from_secs(0));
+                match self.api.execute(method).await {
+                    Ok(_) => true,
+                    Err(err) => {
+                        error!("An error has occurred while confirming offset: {}", err);
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
+        LongPollReport {
+            handled: dispatcher.get_handled(),
+            last_update_id,
+            unhandled: dispatcher.get_unhandled(),
+            offset_confirmed,
+        }
+    }
+}
+
+/// Long poll handle
+#[derive(Clone)]
+pub struct LongPollHandle {
+    sender: Sender<()>,
+    status: Arc<Mutex<LongPollStatus>>,
+}
+
+impl LongPollHandle {
+    /// Returns a current status of polling loop
+    ///
+    /// Use it to detect a stuck polling loop, e.g. in a liveness probe
+    pub fn get_status(&self) -> LongPollStatus {
+        *self.status.lock().unwrap()
+    }
+
+    /// Stop polling loop
+    ///
+    /// Polling loop stops fetching updates immediately,
+    /// waits for running handlers and confirms an offset of handled updates
+    pub async fn shutdown(self) {
+        let _ = self.sender.send(()).await;
+    }
+}
+
+/// A status of long polling
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LongPollStatus {
+    /// Time of the last successful `getUpdates` request
+    pub last_poll_at: Option<SystemTime>,
+    /// Number of failed `getUpdates` requests since the last successful one
+    pub consecutive_errors: u32,
+    /// ID of the last received update
+    ///
+    /// Next `getUpdates` request returns updates after this one
+    pub received_offset: Integer,
+    /// ID of the last update such that all updates before it have been handled
+    ///
+    /// Stops advancing when a handler is stuck.
+    /// Updated by [`LongPoll`] only
+    pub handled_offset: Option<Integer>,
+    /// Number of received updates
+    pub received: u64,
+    /// Number of handled updates
+    ///
+    /// Updated by [`LongPoll`] only
+    pub handled: u64,
+}
+
+/// A report returned by [`LongPoll::run`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LongPollReport {
+    /// Number of handled updates
+    pub handled: u64,
+    /// ID of the last update such that all updates before it have been handled
+    pub last_update_id: Option<Integer>,
+    /// Number of updates which have not been handled before shutdown timeout has elapsed
+    pub unhandled: usize,
+    /// Whether the offset has been confirmed
+    ///
+    /// Updates handled before `last_update_id` will be delivered again when it is `false`
+    pub offset_confirmed: bool,
+}
+
+async fn commit_offset(
+    offset_store: Option<&dyn OffsetStore>,
+    committed: &mut Option<Integer>,
+    update_id: Option<Integer>,
+) {
+    if let (Some(offset_store), Some(update_id)) = (offset_store, update_id) {
+        if *committed == Some(update_id) {
+            return;
+        }
+        match offset_store.commit(update_id).await {
+            Ok(()) => *committed = Some(update_id),
+            Err(err) => error!("An error has occurred while committing offset: {}", err),
+        }
+    }
+}
+
+/// Options for long polling
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LongPollOptions {
+    offset: Integer,
+    limit: Integer,
+    poll_timeout: Duration,
+    error_timeout: Duration,
+    max_error_timeout: Duration,
+    allowed_updates: HashSet<AllowedUpdate>,
+    concurrency_limit: usize,
+    shutdown_timeout: Duration,
+    invalid_update_hook: Option<InvalidUpdateHook>,
+    drop_pending_updates: bool,
+    max_message_age: Option<Duration>,
+    delete_webhook_on_conflict: bool,
+}
+
+impl LongPollOptions {
+    /// Limits the number of updates to be retrieved
+    ///
+    /// Values between 1—100 are accepted
+    ///
+    /// Defaults to 100
+    pub fn limit(mut self, limit: Integer) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Timeout for long polling
+    ///
+    /// 0 - usual short polling
+    ///
+    /// Defaults to 10
+    ///
+    /// Should be positive, short polling should be used for testing purposes only
+    pub fn poll_timeout(mut self, poll_timeout: Duration) -> Self {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+
+    /// Timeout when an error has occurred
+    ///
+    /// Timeout is doubled after each consecutive error up to `max_error_timeout`
+    /// and a random jitter up to a half of the timeout is added to it.
+    /// A delay requested by Telegram using `retry_after` parameter is used as is.
+    ///
+    /// Defaults to 5 seconds
+    pub fn error_timeout(mut self, error_timeout: Duration) -> Self {
+        self.error_timeout = error_timeout;
+        self
+    }
+
+    /// Maximum timeout when errors occur repeatedly
+    ///
+    /// Unlike `error_timeout`, a random jitter is not added to it.
+    ///
+    /// Defaults to 60 seconds
+    pub fn max_error_timeout(mut self, max_error_timeout: Duration) -> Self {
+        self.max_error_timeout = max_error_timeout;
+        self
+    }
+
+    /// Adds a type of updates you want your bot to receive
+    pub fn allowed_update(mut self, allowed_update: AllowedUpdate) -> Self {
+        self.allowed_updates.insert(allowed_update);
+        self
+    }
+
+    /// Maximum number of updates handled concurrently
+    ///
+    /// Updates from the same chat (or from the same user when there is no chat)
+    /// are handled one by one in the order they were received.
+    /// Updates are confirmed only after their handlers have completed,
+    /// so while a handler is running, at most `limit` updates after its update can be received.
+    ///
+    /// Defaults to 1
+    pub fn concurrency_limit(mut self, concurrency_limit: usize) -> Self {
+        self.concurrency_limit = concurrency_limit;
+        self
+    }
+
+    /// Maximum time to wait for running handlers on shutdown
+    ///
+    /// Handlers which have not been completed during this time are cancelled
+    ///
+    /// Defaults to 30 seconds
+    pub fn shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
+    /// Sets a hook called when an update could not be parsed
+    ///
+    /// Such updates are logged and skipped, other updates from the same batch are handled as usual.
+    pub fn on_invalid_update<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(InvalidUpdate) + Send + Sync + 'static,
+    {
+        self.invalid_update_hook = Some(InvalidUpdateHook::new(hook));
+        self
+    }
+
+    /// Whether to skip updates received before start
+    ///
+    /// Defaults to false
+    pub fn drop_pending_updates(mut self, drop_pending_updates: bool) -> Self {
+        self.drop_pending_updates = drop_pending_updates;
+        self
+    }
+
+    /// Skip updates with a message older than the given age
+    ///
+    /// Age is calculated using message edit date or message date when an update is received.
+    /// Updates without a message are never skipped.
+    ///
+    /// By default updates are not skipped
+    pub fn max_message_age(mut self, max_message_age: Duration) -> Self {
+        self.max_message_age = Some(max_message_age);
+        self
+    }
+
+    /// Whether to delete a webhook when Telegram refuses to return updates because it is set
+    ///
+    /// Otherwise an error is logged and the request is repeated after `error_timeout`.
+    /// The webhook is deleted once until updates are received,
+    /// when it is set again, the request is repeated after `error_timeout`.
+    ///
+    /// Defaults to false
+    pub fn delete_webhook_on_conflict(mut self, delete_webhook_on_conflict: bool) -> Self {
+        self.delete_webhook_on_conflict = delete_webhook_on_conflict;
+        self
+    }
+}
+
+impl Default for LongPollOptions {
+    fn default() -> Self {
+        LongPollOptions {
+            offset: 0,
+            limit: DEFAULT_LIMIT,
+            poll_timeout: DEFAULT_POLL_TIMEOUT,
+            error_timeout: DEFAULT_ERROR_TIMEOUT,
+            max_error_timeout: DEFAULT_MAX_ERROR_TIMEOUT,
+            allowed_updates: HashSet::new(),
+            concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            invalid_update_hook: None,
+            drop_pending_updates: false,
+            max_message_age: None,
+            delete_webhook_on_conflict: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{ExecuteError, Transport},
+        request::Request,
+        types::Update,
+    };
+    use futures_util::future::BoxFuture;
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::{Arc, Mutex};
+    use tokio::{spawn, time::sleep};
+
+    struct Handler;
+
+    impl UpdateHandler for Handler {
+        type Future = BoxFuture<'static, ()>;
+
+        fn handle(&self, _update: Update) -> Self::Future {
+            Box::pin(async {})
+        }
+    }
+
+    /// Returns updates for the first request and waits for the others
+    #[derive(Clone)]
+    struct UpdatesTransport {
+        first_update_id: Integer,
+        chats: Vec<Integer>,
+        requests: Arc<Mutex<Vec<JsonValue>>>,
+    }
+
+    impl UpdatesTransport {
+        fn new(first_update_id: Integer) -> Self {
+            Self::with_chats(first_update_id, vec![1, 1, 1])
+        }
+
+        /// Returns an update for each chat
+        fn with_chats(first_update_id: Integer, chats: Vec<Integer>) -> Self {
+            Self {
+                first_update_id,
+                chats,
+                requests: Default::default(),
+            }
+        }
+    }
+
+    impl Transport for UpdatesTransport {
+        fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>> {
+            let params = request.get_body().get_json().unwrap();
+            self.requests.lock().unwrap().push(params.clone());
+            let first_update_id = self.first_update_id;
+            let updates = self
+                .chats
+                .iter()
+                .zip(first_update_id..)
+                .map(|(chat_id, update_id)| create_update(update_id, *chat_id))
+                .collect::<Vec<_>>();
+            Box::pin(async move {
+                let result = if params["offset"] == first_update_id {
+                    JsonValue::from(updates)
+                } else {
+                    if params["limit"] != 1 {
+                        sleep(Duration::from_millis(50)).await;
+                    }
+                    json!([])
+                };
+                Ok(json!({"ok": true, "result": result}))
+            })
+        }
+    }
+
+    fn create_update(update_id: Integer, chat_id: Integer) -> JsonValue {
+        json!({
+            "update_id": update_id,
+            "message": {
+                "message_id": update_id,
+                "date": 0,
+                "from": {"id": chat_id, "is_bot": false, "first_name": "test"},
+                "chat": {"id": chat_id, "type": "private", "first_name": "test"},
+                "text": "test"
+            }
+        })
+    }
+
+    struct SlowHandler {
+        started: Sender<()>,
+        delay: Duration,
+    }
+
+    impl UpdateHandler for SlowHandler {
+        type Future = BoxFuture<'static, ()>;
+
+        fn handle(&self, _update: Update) -> Self::Future {
+            let started = self.started.clone();
+            let delay = self.delay;
+            Box::pin(async move {
+                let _ = started.send(()).await;
+                sleep(delay).await;
+            })
+        }
+    }
+
+    async fn run_until_started(
+        delay: Duration,
+        first_update_id: Integer,
+        offset_store: MemoryOffsetStore,
+    ) -> (LongPollReport, Vec<JsonValue>, LongPollStatus) {
+        let transport = UpdatesTransport::new(first_update_id);
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let (started, mut receiver) = channel(3);
+        let poll = LongPoll::new(api, SlowHandler { started, delay })
+            .options(LongPollOptions::default().shutdown_timeout(Duration::from_millis(100)))
+            .offset_store(offset_store);
+        let handle = poll.get_handle();
+        let status_handle = handle.clone();
+        spawn(async move {
+            receiver.recv().await;
+            handle.shutdown().await;
+        });
+        let report = poll.run().await;
+        let requests = transport.requests.lock().unwrap().clone();
+        (report, requests, status_handle.get_status())
+    }
+
+    #[tokio::test]
+    async fn shutdown() {
+        let (report, requests, status) =
+            run_until_started(Duration::from_millis(20), 1, MemoryOffsetStore::new()).await;
+        assert_eq!(
+            report,
+            LongPollReport {
+                handled: 1,
+                last_update_id: Some(1),
+                unhandled: 0,
+                offset_confirmed: true,
+            }
+        );
+        let confirm = requests.last().unwrap();
+        assert_eq!(confirm["offset"], 2);
+        assert_eq!(confirm["limit"], 1);
+        assert_eq!(confirm["timeout"], 0);
+        assert!(status.last_poll_at.is_some());
+        assert_eq!(status.consecutive_errors, 0);
+        assert_eq!(status.received_offset, 1);
+        assert_eq!(status.handled_offset, Some(1));
+        assert_eq!(status.received, 1);
+        assert_eq!(status.handled, 1);
+
+        let (report, requests, _) = run_until_started(Duration::from_secs(10), 1, MemoryOffsetStore::new()).await;
+        assert_eq!(
+            report,
+            LongPollReport {
+                handled: 0,
+                last_update_id: None,
+                unhandled: 1,
+                offset_confirmed: false,
+            }
+        );
+        assert_eq!(requests.len(), 1);
+    }
+
+    /// Records a maximum offset of `getUpdates` requests sent before an update has been handled
+    struct ConcurrentHandler {
+        requests: Arc<Mutex<Vec<JsonValue>>>,
+        handled: Arc<Mutex<Vec<(Integer, Integer)>>>,
+    }
+
+    impl UpdateHandler for ConcurrentHandler {
+        type Future = BoxFuture<'static, ()>;
+
+        fn handle(&self, update: Update) -> Self::Future {
+            let requests = self.requests.clone();
+            let handled = self.handled.clone();
+            Box::pin(async move {
+                // the first update is still running when others have been handled
+                sleep(Duration::from_millis(if update.id == 1 { 100 } else { 10 })).await;
+                let offset = requests
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|params| params["offset"].as_i64())
+                    .max()
+                    .unwrap();
+                handled.lock().unwrap().push((update.id, offset));
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit() {
+        let transport = UpdatesTransport::with_chats(1, vec![1, 2, 3, 1]);
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handler = ConcurrentHandler {
+            requests: transport.requests.clone(),
+            handled: handled.clone(),
+        };
+        let poll = LongPoll::new(api, handler).options(LongPollOptions::default().concurrency_limit(4));
+        let handle = poll.get_handle();
+        spawn(async move {
+            sleep(Duration::from_millis(300)).await;
+            handle.shutdown().await;
+        });
+        let report = poll.run().await;
+        assert_eq!(
+            report,
+            LongPollReport {
+                handled: 4,
+                last_update_id: Some(4),
+                unhandled: 0,
+                offset_confirmed: true,
+            }
+        );
+        let handled = handled.lock().unwrap().clone();
+        assert_eq!(handled.iter().map(|x| x.0).collect::<Vec<_>>(), vec![2, 3, 1, 4]);
+        // an update is not confirmed until it has been handled
+        for (update_id, offset) in handled {
+            assert!(offset <= update_id);
+        }
+        let requests = transport.requests.lock().unwrap();
+        let offsets = requests
+            .iter()
+            .map(|params| params["offset"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets[0], 1);
+        assert_eq!(offsets[offsets.len() - 1], 5);
+        assert!(offsets.windows(2).all(|x| x[0].as_i64() <= x[1].as_i64()));
+    }
+
+    /// Returns an update from the first chat and then a new update from the second chat
+    #[derive(Clone, Default)]
+    struct NewUpdateTransport {
+        requests: Arc<Mutex<Vec<JsonValue>>>,
+    }
+
+    impl Transport for NewUpdateTransport {
+        fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>> {
+            let params = request.get_body().get_json().unwrap();
+            let mut requests = self.requests.lock().unwrap();
+            let updates = if requests.is_empty() {
+                vec![create_update(1, 1)]
+            } else {
+                vec![create_update(1, 1), create_update(2, 2)]
+            };
+            requests.push(params.clone());
+            let offset = params["offset"].as_i64().unwrap();
+            let updates = updates
+                .into_iter()
+                .filter(|update| update["update_id"].as_i64().unwrap() >= offset)
+                .collect::<Vec<_>>();
+            Box::pin(async move {
+                if updates.is_empty() {
+                    sleep(Duration::from_millis(50)).await;
+                }
+                Ok(json!({"ok": true, "result": updates}))
+            })
+        }
+    }
+
+    /// Handles updates from the first chat slowly
+    struct ChatHandler {
+        handled: Sender<Integer>,
+        started: Arc<Mutex<Vec<Integer>>>,
+    }
+
+    impl UpdateHandler for ChatHandler {
+        type Future = BoxFuture<'static, ()>;
+
+        fn handle(&self, update: Update) -> Self::Future {
+            self.started.lock().unwrap().push(update.id);
+            let handled = self.handled.clone();
+            Box::pin(async move {
+                if update.get_chat_id() == Some(1) {
+                    sleep(Duration::from_secs(10)).await;
+                }
+                let _ = handled.send(update.id).await;
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_handler() {
+        let transport = NewUpdateTransport::default();
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let (handled, mut receiver) = channel(2);
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let handler = ChatHandler {
+            handled,
+            started: started.clone(),
+        };
+        let options = LongPollOptions::default()
+            .concurrency_limit(2)
+            .shutdown_timeout(Duration::from_millis(50));
+        let poll = LongPoll::new(api, handler).options(options);
+        let handle = poll.get_handle();
+        let poll = spawn(poll.run());
+        // a new update is handled while the handler of the first chat is running
+        let update_id = timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+        assert_eq!(update_id, Some(2));
+        handle.shutdown().await;
+        let report = poll.await.unwrap();
+        assert_eq!(
+            report,
+            LongPollReport {
+                handled: 1,
+                last_update_id: None,
+                unhandled: 1,
+                offset_confirmed: false,
+            }
+        );
+        assert_eq!(*started.lock().unwrap(), vec![1, 2]);
+        // the update of the running handler is never confirmed
+        let requests = transport.requests.lock().unwrap();
+        assert!(requests.iter().all(|params| params["offset"] == 1));
+    }
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[test]
+    fn run_is_send() {
+        let api = Api::new("token").unwrap();
+        assert_send(LongPoll::new(api, Handler).run());
+    }
+
+    #[test]
+    fn options() {
+        let options = LongPollOptions::default();
+        assert_eq!(options.limit, DEFAULT_LIMIT);
+        assert_eq!(options.poll_timeout, DEFAULT_POLL_TIMEOUT);
+        assert_eq!(options.error_timeout, DEFAULT_ERROR_TIMEOUT);
+        assert_eq!(options.max_error_timeout, DEFAULT_MAX_ERROR_TIMEOUT);
+        assert!(options.allowed_updates.is_empty());
+        assert_eq!(options.concurrency_limit, DEFAULT_CONCURRENCY_LIMIT);
+        assert_eq!(options.shutdown_timeout, DEFAULT_SHUTDOWN_TIMEOUT);
+        assert!(options.invalid_update_hook.is_none());
+        assert!(!options.drop_pending_updates);
+        assert!(options.max_message_age.is_none());
+        assert!(!options.delete_webhook_on_conflict);
+
+        let options = LongPollOptions::default()
+            .limit(10)
+            .poll_timeout(Duration::from_secs(1))
+            .error_timeout(Duration::from_secs(2))
+            .max_error_timeout(Duration::from_secs(20))
+            .allowed_update(AllowedUpdate::Message)
+            .concurrency_limit(8)
+            .shutdown_timeout(Duration::from_secs(3))
+            .on_invalid_update(|_| {})
+            .drop_pending_updates(true)
+            .max_message_age(Duration::from_secs(60))
+            .delete_webhook_on_conflict(true);
+        assert_eq!(options.limit, 10);
+        assert_eq!(options.poll_timeout, Duration::from_secs(1));
+        assert_eq!(options.error_timeout, Duration::from_secs(2));
+        assert_eq!(options.max_error_timeout, Duration::from_secs(20));
+        assert!(options.allowed_updates.contains(&AllowedUpdate::Message));
+        assert_eq!(options.concurrency_limit, 8);
+        assert_eq!(options.shutdown_timeout, Duration::from_secs(3));
+        assert!(options.invalid_update_hook.is_some());
+        assert!(options.drop_pending_updates);
+        assert_eq!(options.max_message_age, Some(Duration::from_secs(60)));
+        assert!(options.delete_webhook_on_conflict);
+        assert_eq!(options.clone(), options);
+    }
+
+    #[tokio::test]
+    async fn offset_store() {
+        let offset_store = MemoryOffsetStore::new();
+        offset_store.commit(10).await.unwrap();
+        let (report, requests, _) = run_until_started(Duration::from_millis(20), 11, offset_store.clone()).await;
+        assert_eq!(requests[0]["offset"], 11);
+        assert_eq!(report.last_update_id, Some(11));
+        assert_eq!(offset_store.get(), Some(11));
+    }
+
+    #[tokio::test]
+    async fn offset_store_concurrency_limit() {
+        let transport = UpdatesTransport::with_chats(1, vec![1, 2, 3, 1]);
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let (started, mut receiver) = channel(4);
+        let handler = SlowHandler {
+            started,
+            delay: Duration::from_secs(10),
+        };
+        let offset_store = MemoryOffsetStore::new();
+        let options = LongPollOptions::default()
+            .concurrency_limit(4)
+            .shutdown_timeout(Duration::from_millis(50));
+        let poll = LongPoll::new(api, handler)
+            .options(options)
+            .offset_store(offset_store.clone());
+        let handle = poll.get_handle();
+        spawn(async move {
+            for _ in 0..3 {
+                receiver.recv().await;
+            }
+            handle.shutdown().await;
+        });
+        let report = poll.run().await;
+        assert_eq!(report.unhandled, 4);
+        assert!(report.last_update_id.is_none());
+        assert!(offset_store.get().is_none());
+        // cancelled updates are not confirmed, so they are delivered again after restart
+        let requests = transport.requests.lock().unwrap();
+        assert!(requests.iter().all(|params| params["offset"] == 1));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::types::Integer;
+use futures_util::future::BoxFuture;
+use std::{
+    error::Error as StdError,
+    ffi::OsString,
+    io::ErrorKind as IoErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::fs;
+
+/// An error returned by [`OffsetStore`]
+pub type OffsetStoreError = Box<dyn StdError + Send + Sync>;
+
+/// Stores an ID of the last handled update
+///
+/// [`LongPoll`](crate::longpoll::LongPoll) loads the ID at startup
+/// and commits it after each handled update, so polling can be resumed after restart.
+/// An update is committed only when all updates received before it have been handled.
+pub trait OffsetStore: Send + Sync {
+    /// Returns an ID of the last handled update or `None` when nothing has been committed yet
+    fn load(&self) -> BoxFuture<'_, Result<Option<Integer>, OffsetStoreError>>;
+
+    /// Saves an ID of the last handled update
+    ///
+    /// # Arguments
+    ///
+    /// * update_id - ID of the update
+    fn commit(&self, update_id: Integer) -> BoxFuture<'_, Result<(), OffsetStoreError>>;
+}
+
+/// An offset store which keeps the ID in memory
+///
+/// State is shared between clones.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryOffsetStore {
+    update_id: Arc<Mutex<Option<Integer>>>,
+}
+
+impl MemoryOffsetStore {
+    /// Creates a new store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an ID of the last committed update
+    pub fn get(&self) -> Option<Integer> {
+        *self.update_id.lock().unwrap()
+    }
+}
+
+impl OffsetStore for MemoryOffsetStore {
+    fn load(&self) -> BoxFuture<'_, Result<Option<Integer>, OffsetStoreError>> {
+        let update_id = self.get();
+        Box::pin(async move { Ok(update_id) })
+    }
+
+    fn commit(&self, update_id: Integer) -> BoxFuture<'_, Result<(), OffsetStoreError>> {
+        *self.update_id.lock().unwrap() = Some(update_id);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// An offset store which keeps the ID in a file
+///
+/// The file is replaced atomically: the ID is written to a temporary file
+/// with `.tmp` suffix which is renamed then.
+#[derive(Clone, Debug)]
+pub struct FileOffsetStore {
+    path: PathBuf,
+}
+
+impl FileOffsetStore {
+    /// Creates a new store
+    ///
+    /// # Arguments
+    ///
+    /// * path - Path to the file, it is created on first commit
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    async fn read(&self) -> Result<Option<Integer>, OffsetStoreError> {
+        match fs::read_to_string(&self.path).await {
+            Ok(data) => Ok(Some(data.trim().parse()?)),
+            Err(err) if err.kind() == IoErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn write(&self, update_id: Integer) -> Result<(), OffsetStoreError> {
+        let mut tmp_path = OsString::from(self.path.as_os_str());
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, format!("{}\n", update_id)).await?;
+        fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+impl OffsetStore for FileOffsetStore {
+    fn load(&self) -> BoxFuture<'_, Result<Option<Integer>, OffsetStoreError>> {
+        Box::pin(self.read())
+    }
+
+    fn commit(&self, update_id: Integer) -> BoxFuture<'_, Result<(), OffsetStoreError>> {
+        Box::pin(self.write(update_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn memory_offset_store() {
+        let store = MemoryOffsetStore::new();
+        assert!(store.load().await.unwrap().is_none());
+        store.commit(1).await.unwrap();
+        store.clone().commit(2).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(2));
+        assert_eq!(store.get(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn file_offset_store() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("offset");
+        let store = FileOffsetStore::new(&path);
+        assert!(store.load().await.unwrap().is_none());
+        store.commit(1).await.unwrap();
+        store.commit(2).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(2));
+        assert_eq!(FileOffsetStore::new(&path).load().await.unwrap(), Some(2));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "2\n");
+
+        std::fs::write(&path, "invalid").unwrap();
+        assert!(store.load().await.is_err());
+    }
+}
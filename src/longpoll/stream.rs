@@ -0,0 +1,508 @@
+use crate::{
+    api::{get_exponential_backoff, jitter, Api, ExecuteError},
+    longpoll::{InvalidUpdate, LongPollOptions, LongPollStatus},
+    methods::{DeleteWebhook, GetUpdates, Method},
+    request::Request,
+    types::{Integer, ResponseErrorKind, Update},
+};
+use async_stream::stream;
+use futures_util::stream::{BoxStream, Stream};
+use log::{debug, error, info, warn};
+use serde_json::Value as JsonValue;
+use std::{
+    cmp::{max, min},
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::watch::Receiver,
+    time::{sleep, timeout},
+};
+
+/// How often to request updates when Telegram returns only updates which are being handled
+const DUPLICATES_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A stream of updates received using long polling
+///
+/// Offset is advanced when updates are received,
+/// so an update is confirmed by the next `getUpdates` request
+/// and will not be delivered again even if it has not been processed.
+/// Use [`LongPoll`](crate::longpoll::LongPoll) with an
+/// [`OffsetStore`](crate::longpoll::OffsetStore) when each update must be processed.
+///
+/// Errors are logged and the request is repeated after a delay,
+/// see [`LongPollOptions::error_timeout`], so the stream never ends.
+/// Updates which could not be parsed are skipped,
+/// see [`LongPollOptions::on_invalid_update`].
+/// `concurrency_limit` and `shutdown_timeout` options are not used here.
+///
+/// # Example
+///
+/// ```
+/// # async fn stream() {
+/// use futures_util::stream::StreamExt;
+/// use tgbot::{longpoll::{LongPollOptions, LongPollStream}, Api};
+///
+/// let api = Api::new("token").unwrap();
+/// let mut updates = LongPollStream::new(api, LongPollOptions::default());
+/// while let Some(update) = updates.next().await {
+///     println!("{:?}", update);
+/// }
+/// # }
+/// ```
+pub struct LongPollStream {
+    inner: BoxStream<'static, Update>,
+    status: Arc<Mutex<LongPollStatus>>,
+}
+
+impl LongPollStream {
+    /// Creates a new stream
+    ///
+    /// # Arguments
+    ///
+    /// * api - Telegram Bot API Client
+    /// * options - Poll options
+    pub fn new(api: Api, options: LongPollOptions) -> Self {
+        Self::with_status(api, options, Default::default(), None)
+    }
+
+    /// Creates a stream which does not confirm updates until they have been handled
+    ///
+    /// `pending` is an ID of the first update which has been received but not handled yet.
+    /// Updates are requested starting from it and already received updates are skipped.
+    pub(super) fn with_status(
+        api: Api,
+        options: LongPollOptions,
+        status: Arc<Mutex<LongPollStatus>>,
+        mut pending: Option<Receiver<Option<Integer>>>,
+    ) -> Self {
+        let LongPollOptions {
+            mut offset,
+            limit,
+            poll_timeout,
+            error_timeout,
+            max_error_timeout,
+            allowed_updates,
+            invalid_update_hook,
+            drop_pending_updates,
+            max_message_age,
+            delete_webhook_on_conflict,
+            ..
+        } = options;
+        status.lock().unwrap().received_offset = offset;
+        let shared_status = status.clone();
+        let inner = stream! {
+            if drop_pending_updates {
+                let method = GetUpdates::default()
+                    .offset(-1)
+                    .limit(1)
+                    .timeout(Duration::from_secs(0));
+                match api.execute(GetRawUpdates(method)).await {
+                    Ok(updates) => {
+                        if let Some(update_id) = updates.last().and_then(get_update_id) {
+                            info!("Dropping pending updates up to {}", update_id);
+                            offset = max(offset, update_id);
+                        }
+                    }
+                    Err(err) => error!("An error has occurred while dropping pending updates: {}", err),
+                }
+            }
+            let mut has_duplicates = false;
+            let mut webhook_deleted = false;
+            loop {
+                if let (true, Some(pending)) = (has_duplicates, pending.as_mut()) {
+                    // Telegram returns unconfirmed updates immediately, so wait for handlers
+                    if let Ok(Err(_)) = timeout(DUPLICATES_POLL_INTERVAL, pending.changed()).await {
+                        return;
+                    }
+                }
+                let first_pending = pending.as_ref().and_then(|pending| *pending.borrow());
+                // a request confirms updates before the offset, so pending updates must not be confirmed
+                let request_offset = match first_pending {
+                    Some(update_id) => update_id,
+                    None => offset + 1,
+                };
+                let method = GetUpdates::default()
+                    .offset(request_offset)
+                    .limit(limit)
+                    .timeout(poll_timeout)
+                    .allowed_updates(allowed_updates.clone());
+                let updates = match api.execute(GetRawUpdates(method)).await {
+                    Ok(updates) => {
+                        let mut status = status.lock().unwrap();
+                        status.last_poll_at = Some(SystemTime::now());
+                        status.consecutive_errors = 0;
+                        webhook_deleted = false;
+                        updates
+                    }
+                    Err(err) => {
+                        let consecutive_errors = {
+                            let mut status = status.lock().unwrap();
+                            status.consecutive_errors = status.consecutive_errors.saturating_add(1);
+                            status.consecutive_errors
+                        };
+                        if is_webhook_conflict(&err) {
+                            if delete_webhook_on_conflict && !webhook_deleted {
+                                warn!("Webhook is set, deleting it in order to get updates using long polling");
+                                match api.execute(DeleteWebhook::default()).await {
+                                    Ok(_) => {
+                                        webhook_deleted = true;
+                                        continue
+                                    }
+                                    Err(err) => error!("An error has occurred while deleting webhook: {}", err),
+                                }
+                            } else if webhook_deleted {
+                                error!(
+                                    "Webhook has been set again after it was deleted, \
+                                    probably it is used by another instance of the bot"
+                                );
+                            } else {
+                                error!(
+                                    "Can not get updates while webhook is set, \
+                                    delete it using DeleteWebhook method \
+                                    or enable LongPollOptions::delete_webhook_on_conflict"
+                                );
+                            }
+                        } else {
+                            error!("An error has occurred while getting updates: {}", err);
+                        }
+                        let error_timeout = get_error_timeout(err, error_timeout, max_error_timeout, consecutive_errors);
+                        sleep(error_timeout).await;
+                        continue
+                    }
+                };
+                has_duplicates = !updates.is_empty();
+                for data in updates {
+                    let update_id = match get_update_id(&data) {
+                        Some(update_id) => update_id,
+                        None => {
+                            error!("Got an update without update_id: {}", data);
+                            continue
+                        }
+                    };
+                    if first_pending.is_some() && update_id <= offset {
+                        // has been received already and is waiting for a running handler
+                        continue
+                    }
+                    has_duplicates = false;
+                    offset = update_id;
+                    status.lock().unwrap().received_offset = offset;
+                    match Update::from_value_strict(&data) {
+                        Ok(update) => match max_message_age {
+                            Some(max_message_age) if is_stale(&update, max_message_age) => {
+                                debug!("Skipping stale update {}", update_id);
+                            }
+                            _ => {
+                                status.lock().unwrap().received += 1;
+                                yield update
+                            }
+                        },
+                        Err(err) => {
+                            error!("Failed to parse update {}: {}", update_id, err);
+                            if let Some(ref hook) = invalid_update_hook {
+                                hook.call(InvalidUpdate {
+                                    update_id,
+                                    data,
+                                    error: err,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        Self {
+            inner: Box::pin(inner),
+            status: shared_status,
+        }
+    }
+
+    /// Returns a current status
+    pub fn get_status(&self) -> LongPollStatus {
+        *self.status.lock().unwrap()
+    }
+}
+
+impl Stream for LongPollStream {
+    type Item = Update;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for LongPollStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LongPollStream").finish()
+    }
+}
+
+/// Returns updates as is in order to parse them one by one
+struct GetRawUpdates(GetUpdates);
+
+impl Method for GetRawUpdates {
+    type Response = Vec<JsonValue>;
+
+    fn into_request(self) -> Request {
+        self.0.into_request()
+    }
+}
+
+fn get_update_id(data: &JsonValue) -> Option<Integer> {
+    data.get("update_id").and_then(JsonValue::as_i64)
+}
+
+fn is_stale(update: &Update, max_message_age: Duration) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default();
+    match update.get_message() {
+        Some(message) => {
+            let date = message.edit_date.unwrap_or(message.date);
+            (date as u64).saturating_add(max_message_age.as_secs()) < now
+        }
+        None => false,
+    }
+}
+
+fn is_webhook_conflict(err: &ExecuteError) -> bool {
+    match err {
+        ExecuteError::Response(err) => {
+            err.kind() == ResponseErrorKind::Conflict && err.description().to_lowercase().contains("webhook")
+        }
+        _ => false,
+    }
+}
+
+fn get_error_timeout(
+    err: ExecuteError,
+    error_timeout: Duration,
+    max_error_timeout: Duration,
+    consecutive_errors: u32,
+) -> Duration {
+    let retry_after = match err {
+        ExecuteError::Response(err) => err.retry_after(),
+        _ => None,
+    };
+    match retry_after {
+        Some(retry_after) => Duration::from_secs(retry_after as u64),
+        None => {
+            let max_error_timeout = max_error_timeout.max(error_timeout);
+            let backoff = get_exponential_backoff(error_timeout, max_error_timeout, consecutive_errors);
+            // error_timeout is the minimum delay, so the jitter is added on top of it
+            min(backoff + jitter(backoff / 2), max_error_timeout)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{DryRunTransport, Transport},
+        types::{Integer, Response},
+    };
+    use futures_util::{future::BoxFuture, stream::StreamExt};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tokio::time::timeout;
+
+    fn create_update(update_id: Integer) -> JsonValue {
+        create_update_with_date(update_id, 0)
+    }
+
+    fn create_update_with_date(update_id: Integer, date: u64) -> JsonValue {
+        json!({
+            "update_id": update_id,
+            "message": {
+                "message_id": update_id,
+                "date": date,
+                "from": {"id": 1, "is_bot": false, "first_name": "test"},
+                "chat": {"id": 1, "type": "private", "first_name": "test"},
+                "text": "test"
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn long_poll_stream() {
+        let transport = DryRunTransport::new().response("getUpdates", json!([create_update(1), create_update(2)]));
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let options = LongPollOptions::default().limit(2);
+        let updates = LongPollStream::new(api, options).take(3).collect::<Vec<_>>().await;
+        assert_eq!(updates.iter().map(|x| x.id).collect::<Vec<_>>(), vec![1, 2, 1]);
+        let offsets = transport
+            .take_requests()
+            .iter()
+            .map(|x| x.get_body().get_json().unwrap()["offset"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![json!(1), json!(3)]);
+    }
+
+    #[tokio::test]
+    async fn invalid_update() {
+        let transport = DryRunTransport::new().response(
+            "getUpdates",
+            json!([
+                create_update(1),
+                {"update_id": 2, "message": {"message_id": 2}},
+                create_update(3)
+            ]),
+        );
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let invalid = Arc::new(Mutex::new(Vec::new()));
+        let options = LongPollOptions::default().on_invalid_update({
+            let invalid = invalid.clone();
+            move |update| invalid.lock().unwrap().push((update.update_id, update.data))
+        });
+        let updates = LongPollStream::new(api, options).take(3).collect::<Vec<_>>().await;
+        assert_eq!(updates.iter().map(|x| x.id).collect::<Vec<_>>(), vec![1, 3, 1]);
+        assert_eq!(
+            *invalid.lock().unwrap(),
+            vec![(2, json!({"update_id": 2, "message": {"message_id": 2}}))]
+        );
+        let requests = transport.take_requests();
+        assert_eq!(requests[1].get_body().get_json().unwrap()["offset"], 4);
+    }
+
+    #[tokio::test]
+    async fn drop_pending_and_stale_updates() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let transport =
+            DryRunTransport::new().response("getUpdates", json!([create_update(1), create_update_with_date(2, now)]));
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let options = LongPollOptions::default()
+            .drop_pending_updates(true)
+            .max_message_age(Duration::from_secs(60));
+        let updates = LongPollStream::new(api, options).take(2).collect::<Vec<_>>().await;
+        assert_eq!(updates.iter().map(|x| x.id).collect::<Vec<_>>(), vec![2, 2]);
+        let offsets = transport
+            .take_requests()
+            .iter()
+            .map(|x| x.get_body().get_json().unwrap()["offset"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![json!(-1), json!(3), json!(3)]);
+    }
+
+    #[test]
+    fn stale_edited_message() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let max_message_age = Duration::from_secs(60);
+        for kind in &["edited_message", "edited_channel_post"] {
+            let mut data = create_update(1);
+            data[kind] = data["message"].take();
+            data.as_object_mut().unwrap().remove("message");
+            data[kind]["edit_date"] = json!(now);
+            let update: Update = serde_json::from_value(data.clone()).unwrap();
+            assert!(!is_stale(&update, max_message_age));
+            data[kind]["edit_date"] = json!(0);
+            let update: Update = serde_json::from_value(data).unwrap();
+            assert!(is_stale(&update, max_message_age));
+        }
+    }
+
+    /// Refuses to return updates until the webhook is deleted
+    #[derive(Clone, Default)]
+    struct WebhookTransport {
+        requests: Arc<Mutex<Vec<String>>>,
+        /// Webhook is set again right after it has been deleted
+        is_reset: bool,
+    }
+
+    impl Transport for WebhookTransport {
+        fn send(&self, request: Request) -> BoxFuture<'_, Result<JsonValue, ExecuteError>> {
+            let path = request.get_path().to_string();
+            let mut requests = self.requests.lock().unwrap();
+            let webhook_deleted = !self.is_reset && requests.iter().any(|x| x == "deleteWebhook");
+            let response = match path.as_str() {
+                "getUpdates" if !webhook_deleted => json!({
+                    "ok": false,
+                    "error_code": 409,
+                    "description": "Conflict: can't use getUpdates method while webhook is active; \
+                                    use deleteWebhook to delete the webhook first"
+                }),
+                "getUpdates" => json!({"ok": true, "result": [create_update(1)]}),
+                _ => json!({"ok": true, "result": true}),
+            };
+            requests.push(path);
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_webhook_on_conflict() {
+        let transport = WebhookTransport::default();
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let options = LongPollOptions::default()
+            .error_timeout(Duration::from_secs(60))
+            .delete_webhook_on_conflict(true);
+        let mut updates = LongPollStream::new(api, options);
+        assert_eq!(updates.get_status(), LongPollStatus::default());
+        let update = timeout(Duration::from_secs(1), updates.next()).await.unwrap().unwrap();
+        assert_eq!(update.id, 1);
+        let status = updates.get_status();
+        assert!(status.last_poll_at.is_some());
+        assert_eq!(status.consecutive_errors, 0);
+        assert_eq!(status.received_offset, 1);
+        assert_eq!(status.received, 1);
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec!["getUpdates", "deleteWebhook", "getUpdates"]
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_webhook_once_on_conflict() {
+        let transport = WebhookTransport {
+            is_reset: true,
+            ..Default::default()
+        };
+        let api = Api::new("token").unwrap().with_transport(transport.clone());
+        let options = LongPollOptions::default()
+            .error_timeout(Duration::from_secs(60))
+            .delete_webhook_on_conflict(true);
+        let mut updates = LongPollStream::new(api, options);
+        assert!(timeout(Duration::from_millis(100), updates.next()).await.is_err());
+        assert_eq!(updates.get_status().consecutive_errors, 2);
+        // the webhook is not deleted again until updates are received
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec!["getUpdates", "deleteWebhook", "getUpdates"]
+        );
+    }
+
+    #[test]
+    fn error_timeout() {
+        let error_timeout = Duration::from_secs(2);
+        let max_error_timeout = Duration::from_secs(6);
+        let err = || ExecuteError::Json(serde_json::from_str::<JsonValue>("").unwrap_err());
+        let delay = get_error_timeout(err(), error_timeout, max_error_timeout, 1);
+        assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(3));
+        let delay = get_error_timeout(err(), error_timeout, max_error_timeout, 2);
+        assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(6));
+        assert_eq!(
+            get_error_timeout(err(), error_timeout, max_error_timeout, 10),
+            max_error_timeout
+        );
+
+        let err = match serde_json::from_value::<Response<()>>(json!({
+            "ok": false,
+            "description": "Too Many Requests: retry after 10",
+            "error_code": 429,
+            "parameters": {"retry_after": 10}
+        }))
+        .unwrap()
+        {
+            Response::Error(err) => ExecuteError::Response(err),
+            Response::Success(_) => unreachable!(),
+        };
+        assert_eq!(
+            get_error_timeout(err, error_timeout, max_error_timeout, 10),
+            Duration::from_secs(10)
+        );
+    }
+}
@@ -3,7 +3,8 @@ use serde::Serialize;
 
 /// Get basic info about a file and prepare it for downloading
 ///
-/// For the moment, bots can download files of up to 20MB in size
+/// For the moment, bots can download files of up to 20MB in size,
+/// there is no limit when using a local Bot API server
 ///
 /// The file can then be downloaded via the link
 /// `https://api.telegram.org/file/bot<token>/<file_path>`,
@@ -6,5 +6,7 @@ pub trait Method {
     type Response;
 
     /// Returns information about HTTP request
+    ///
+    /// Use it to inspect a method name and parameters, see [`Request`] for details
     fn into_request(self) -> Request;
 }
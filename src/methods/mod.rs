@@ -8,6 +8,7 @@ mod message;
 mod method;
 mod passport;
 mod poll;
+mod raw;
 mod send;
 mod sticker;
 mod updates;
@@ -15,5 +16,5 @@ mod user;
 
 pub use self::{
     answer::*, chat::*, chat_member::*, commands::*, game::*, get_file::*, message::*, method::*, passport::*, poll::*,
-    send::*, sticker::*, updates::*, user::*,
+    raw::*, send::*, sticker::*, updates::*, user::*,
 };
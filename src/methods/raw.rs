@@ -0,0 +1,152 @@
+use crate::{
+    methods::Method,
+    request::{Form, Request},
+    types::InputFile,
+};
+use serde_json::Value as JsonValue;
+use std::{fmt, marker::PhantomData};
+
+/// Executes an arbitrary method
+///
+/// Useful for methods which are not supported by the crate yet
+///
+/// Parameters are sent as JSON, or as multipart form when at least one file is added.
+/// In the latter case top-level string parameters are sent as is
+/// and other parameters are serialized to JSON.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use tgbot::{methods::RawMethod, types::Message};
+///
+/// let method = RawMethod::<Message>::new("sendMessage", json!({"chat_id": 1, "text": "text"}));
+/// ```
+pub struct RawMethod<T> {
+    name: String,
+    params: JsonValue,
+    files: Vec<(String, InputFile)>,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<T> RawMethod<T> {
+    /// Creates a new RawMethod
+    ///
+    /// # Arguments
+    ///
+    /// * name - Name of the API method
+    /// * params - Parameters of the method, an object or null
+    pub fn new<N: Into<String>>(name: N, params: JsonValue) -> Self {
+        Self {
+            name: name.into(),
+            params,
+            files: Vec::new(),
+            response: PhantomData,
+        }
+    }
+
+    /// Adds a file to upload
+    ///
+    /// # Arguments
+    ///
+    /// * name - Name of the parameter
+    /// * file - File to upload
+    pub fn file<N, F>(mut self, name: N, file: F) -> Self
+    where
+        N: Into<String>,
+        F: Into<InputFile>,
+    {
+        self.files.push((name.into(), file.into()));
+        self
+    }
+}
+
+impl<T> fmt::Debug for RawMethod<T> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("RawMethod")
+            .field("name", &self.name)
+            .field("params", &self.params)
+            .field("files", &self.files)
+            .finish()
+    }
+}
+
+impl<T> Method for RawMethod<T> {
+    type Response = T;
+
+    fn into_request(self) -> Request {
+        if self.files.is_empty() {
+            return match self.params {
+                JsonValue::Null => Request::empty(self.name),
+                params => Request::json(self.name, params),
+            };
+        }
+        let mut form = Form::new();
+        if let JsonValue::Object(params) = self.params {
+            for (name, value) in params {
+                form.insert_json_field(name, value);
+            }
+        }
+        for (name, file) in self.files {
+            form.insert_field(name, file);
+        }
+        Request::form(self.name, form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        request::{RequestBody, RequestMethod},
+        types::Message,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn raw_method() {
+        let request = RawMethod::<bool>::new("logOut", JsonValue::Null).into_request();
+        assert_eq!(request.get_method(), RequestMethod::Get);
+        assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/logOut");
+        match request.into_body() {
+            RequestBody::Empty => {}
+            data => panic!("Unexpected request data: {:?}", data),
+        }
+
+        let request = RawMethod::<Message>::new("sendMessage", json!({"chat_id": 1, "text": "text"})).into_request();
+        assert_eq!(request.get_method(), RequestMethod::Post);
+        assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/sendMessage");
+        match request.into_body() {
+            RequestBody::Json(data) => {
+                assert_eq!(data.unwrap(), r#"{"chat_id":1,"text":"text"}"#);
+            }
+            data => panic!("Unexpected request data: {:?}", data),
+        }
+
+        let request = RawMethod::<Message>::new(
+            "sendPhoto",
+            json!({
+                "chat_id": 1,
+                "caption": "caption",
+                "reply_markup": {"force_reply": true},
+                "parse_mode": null
+            }),
+        )
+        .file("photo", InputFile::file_id("file-id"))
+        .into_request();
+        assert_eq!(request.get_method(), RequestMethod::Post);
+        assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/sendPhoto");
+        if let RequestBody::Form(form) = request.into_body() {
+            assert_eq!(form.fields["chat_id"].get_text().unwrap(), "1");
+            assert_eq!(form.fields["caption"].get_text().unwrap(), "caption");
+            assert_eq!(
+                form.fields["reply_markup"].get_text().unwrap(),
+                r#"{"force_reply":true}"#
+            );
+            assert!(!form.fields.contains_key("parse_mode"));
+            assert!(form.fields["photo"].get_file().is_some());
+        } else {
+            panic!("Unexpected request body");
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
     methods::Method,
-    request::Request,
-    types::{AllowedUpdate, Integer, Update, WebhookInfo},
+    request::{Form, Request},
+    types::{AllowedUpdate, InputFile, Integer, Update, WebhookInfo},
 };
 use serde::Serialize;
+use serde_json::Value as JsonValue;
 use std::{collections::HashSet, time::Duration};
 
 /// Receive incoming updates using long polling
@@ -25,7 +26,8 @@ impl Method for GetUpdates {
     type Response = Vec<Update>;
 
     fn into_request(self) -> Request {
-        Request::json("getUpdates", self)
+        let timeout = Duration::from_secs(self.timeout.unwrap_or_default() as u64);
+        Request::json("getUpdates", self).extra_timeout(timeout)
     }
 }
 
@@ -97,11 +99,11 @@ impl GetUpdates {
 /// If you'd like to make sure that the Webhook request comes from Telegram,
 /// we recommend using a secret path in the URL, e.g. `https://www.example.com/<token>`
 /// Since nobody else knows your bot‘s token, you can be pretty sure it’s us
-#[derive(Clone, Debug, Serialize)]
+#[derive(Debug, Serialize)]
 pub struct SetWebhook {
     url: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    certificate: Option<String>,
+    #[serde(skip)]
+    certificate: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ip_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -110,6 +112,8 @@ pub struct SetWebhook {
     allowed_updates: Option<HashSet<AllowedUpdate>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     drop_pending_updates: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_token: Option<String>,
 }
 
 impl SetWebhook {
@@ -127,11 +131,15 @@ impl SetWebhook {
             max_connections: None,
             allowed_updates: None,
             drop_pending_updates: None,
+            secret_token: None,
         }
     }
 
     /// Upload your public key certificate so that the root certificate in use can be checked
-    pub fn certificate<C: Into<String>>(mut self, certificate: C) -> Self {
+    ///
+    /// Required when a self-signed certificate is used.
+    /// The certificate is uploaded using multipart/form-data (url and file_id are not supported).
+    pub fn certificate<C: Into<InputFile>>(mut self, certificate: C) -> Self {
         self.certificate = Some(certificate.into());
         self
     }
@@ -186,13 +194,37 @@ impl SetWebhook {
         self.drop_pending_updates = Some(drop_pending_updates);
         self
     }
+
+    /// A secret token to be sent in a header “X-Telegram-Bot-Api-Secret-Token” in every webhook request
+    ///
+    /// 1-256 characters, only characters A-Z, a-z, 0-9, _ and - are allowed
+    /// The header is useful to ensure that the request comes from a webhook set by you
+    pub fn secret_token<T: Into<String>>(mut self, secret_token: T) -> Self {
+        self.secret_token = Some(secret_token.into());
+        self
+    }
 }
 
 impl Method for SetWebhook {
     type Response = bool;
 
-    fn into_request(self) -> Request {
-        Request::json("setWebhook", self)
+    fn into_request(mut self) -> Request {
+        match self.certificate.take() {
+            Some(certificate) => {
+                let fields = match serde_json::to_value(&self) {
+                    Ok(JsonValue::Object(fields)) => fields,
+                    // serialization fails again and the error is returned when the request is executed
+                    _ => return Request::json("setWebhook", self),
+                };
+                let mut form = Form::new();
+                for (name, value) in fields {
+                    form.insert_json_field(name, value);
+                }
+                form.insert_field("certificate", certificate);
+                Request::form("setWebhook", form)
+            }
+            None => Request::json("setWebhook", self),
+        }
     }
 }
 
@@ -241,11 +273,13 @@ mod tests {
     use super::*;
     use crate::request::{RequestBody, RequestMethod};
     use serde_json::Value;
+    use std::io::Cursor;
 
     #[test]
     fn get_updates() {
         let request = GetUpdates::default().into_request();
         assert_eq!(request.get_method(), RequestMethod::Post);
+        assert_eq!(request.get_extra_timeout(), Duration::from_secs(0));
         assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/getUpdates");
         match request.into_body() {
             RequestBody::Json(data) => {
@@ -271,6 +305,7 @@ mod tests {
             .add_allowed_update(AllowedUpdate::PreCheckoutQuery)
             .add_allowed_update(AllowedUpdate::ShippingQuery)
             .into_request();
+        assert_eq!(request.get_extra_timeout(), Duration::from_secs(10));
         match request.into_body() {
             RequestBody::Json(data) => {
                 let data: Value = serde_json::from_str(&data.unwrap()).unwrap();
@@ -326,7 +361,6 @@ mod tests {
         updates.insert(AllowedUpdate::EditedChannelPost);
         updates.insert(AllowedUpdate::ChosenInlineResult);
         let request = SetWebhook::new("url")
-            .certificate("cert")
             .ip_address("127.0.0.1")
             .max_connections(10)
             .allowed_updates(updates)
@@ -335,16 +369,17 @@ mod tests {
             .add_allowed_update(AllowedUpdate::PreCheckoutQuery)
             .add_allowed_update(AllowedUpdate::ShippingQuery)
             .drop_pending_updates(true)
+            .secret_token("secret-token")
             .into_request();
         assert_eq!(request.get_method(), RequestMethod::Post);
         assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/setWebhook");
         match request.into_body() {
             RequestBody::Json(data) => {
                 let data: Value = serde_json::from_str(&data.unwrap()).unwrap();
-                assert_eq!(data["certificate"], "cert");
                 assert_eq!(data["ip_address"], "127.0.0.1");
                 assert_eq!(data["max_connections"], 10);
                 assert!(data["drop_pending_updates"].as_bool().unwrap());
+                assert_eq!(data["secret_token"], "secret-token");
                 let mut updates: Vec<&str> = data["allowed_updates"]
                     .as_array()
                     .unwrap()
@@ -370,6 +405,25 @@ mod tests {
             data => panic!("Unexpected request data: {:?}", data),
         }
 
+        let request = SetWebhook::new("url")
+            .certificate(InputFile::reader(Cursor::new(b"cert")))
+            .max_connections(10)
+            .add_allowed_update(AllowedUpdate::Message)
+            .secret_token("secret-token")
+            .into_request();
+        assert_eq!(request.get_method(), RequestMethod::Post);
+        assert_eq!(request.build_url("base-url", "token"), "base-url/bottoken/setWebhook");
+        if let RequestBody::Form(form) = request.into_body() {
+            assert_eq!(form.fields["url"].get_text().unwrap(), "url");
+            assert_eq!(form.fields["max_connections"].get_text().unwrap(), "10");
+            assert_eq!(form.fields["allowed_updates"].get_text().unwrap(), r#"["message"]"#);
+            assert_eq!(form.fields["secret_token"].get_text().unwrap(), "secret-token");
+            assert!(form.fields["certificate"].get_file().is_some());
+            assert!(!form.fields.contains_key("ip_address"));
+        } else {
+            panic!("Unexpected request body");
+        }
+
         let method = SetWebhook::new("url").add_allowed_update(AllowedUpdate::Message);
         assert_eq!(method.allowed_updates.unwrap().len(), 1);
     }
@@ -3,31 +3,48 @@ use reqwest::{
     multipart::{Form as MultipartForm, Part},
     Body, Error as ReqwestError,
 };
+use serde_json::Value as JsonValue;
 use std::{collections::HashMap, error::Error as StdError, fmt, io::Error as IoError};
 
+/// Value of a multipart form field
 #[derive(Debug)]
-pub(crate) enum FormValue {
+pub enum FormValue {
+    /// Text value
     Text(String),
+    /// File to upload
     File(InputFile),
 }
 
 impl FormValue {
-    #[cfg(test)]
-    pub(crate) fn get_text(&self) -> Option<&str> {
+    /// Returns a text value
+    pub fn get_text(&self) -> Option<&str> {
         match self {
             FormValue::Text(ref text) => Some(text),
             FormValue::File(_) => None,
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn get_file(&self) -> Option<&InputFile> {
+    /// Returns a file value
+    pub fn get_file(&self) -> Option<&InputFile> {
         match self {
             FormValue::Text(_) => None,
             FormValue::File(ref file) => Some(file),
         }
     }
 
+    fn try_clone(&self) -> Option<Self> {
+        Some(match self {
+            FormValue::Text(text) => FormValue::Text(text.clone()),
+            FormValue::File(file) => FormValue::File(InputFile {
+                kind: match file.kind {
+                    InputFileKind::Id(ref file_id) => InputFileKind::Id(file_id.clone()),
+                    InputFileKind::Url(ref url) => InputFileKind::Url(url.clone()),
+                    InputFileKind::Reader(_) => return None,
+                },
+            }),
+        })
+    }
+
     async fn into_part(self) -> Result<Part, FormError> {
         Ok(match self {
             FormValue::Text(text) => Part::text(text),
@@ -75,17 +92,27 @@ impl From<InputFile> for FormValue {
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct Form {
+/// Multipart form
+#[derive(Debug, Default)]
+pub struct Form {
     pub(crate) fields: HashMap<String, FormValue>,
 }
 
 impl Form {
-    pub(crate) fn new() -> Self {
+    /// Creates a new empty form
+    pub fn new() -> Self {
         Self { fields: HashMap::new() }
     }
 
-    pub(crate) fn insert_field<N, V>(&mut self, name: N, value: V)
+    /// Inserts a field
+    ///
+    /// Existing field with the same name is replaced
+    ///
+    /// # Arguments
+    ///
+    /// * name - Name of the field
+    /// * value - Text or [`InputFile`]
+    pub fn insert_field<N, V>(&mut self, name: N, value: V)
     where
         N: Into<String>,
         V: Into<FormValue>,
@@ -93,14 +120,51 @@ impl Form {
         self.fields.insert(name.into(), value.into());
     }
 
-    pub(crate) fn remove_field<N>(&mut self, name: N)
+    /// Inserts a serialized parameter
+    ///
+    /// Strings are inserted as is, other values are inserted as JSON and null values are skipped
+    pub(crate) fn insert_json_field<N>(&mut self, name: N, value: JsonValue)
+    where
+        N: Into<String>,
+    {
+        match value {
+            JsonValue::Null => {}
+            // Display of a JSON string adds quotes
+            JsonValue::String(value) => self.insert_field(name, value),
+            value => self.insert_field(name, value),
+        }
+    }
+
+    /// Returns a value of the field with a given name
+    pub fn get_field(&self, name: &str) -> Option<&FormValue> {
+        self.fields.get(name)
+    }
+
+    /// Returns an iterator over all fields in arbitrary order
+    pub fn iter_fields(&self) -> impl Iterator<Item = (&str, &FormValue)> {
+        self.fields.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Removes a field
+    pub fn remove_field<N>(&mut self, name: N)
     where
         N: Into<String>,
     {
         self.fields.remove(&name.into());
     }
 
-    pub(crate) async fn into_multipart(self) -> Result<MultipartForm, FormError> {
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        let mut fields = HashMap::with_capacity(self.fields.len());
+        for (name, value) in &self.fields {
+            fields.insert(name.clone(), value.try_clone()?);
+        }
+        Some(Self { fields })
+    }
+
+    /// Converts the form into a reqwest multipart form
+    ///
+    /// File readers are streamed without buffering
+    pub async fn into_multipart(self) -> Result<MultipartForm, FormError> {
         let mut result = MultipartForm::new();
         for (field_name, field_value) in self.fields {
             let field_value = field_value.into_part().await?;
@@ -168,4 +232,46 @@ mod tests {
         form.insert_field("file-reader", InputFile::from(Cursor::new(b"test")));
         form.into_multipart().await.unwrap();
     }
+
+    #[test]
+    fn get_fields() {
+        let mut form = Form::new();
+        form.insert_field("id", 1);
+        form.insert_field("file-id", InputFile::file_id("file-id"));
+        assert_eq!(form.get_field("id").unwrap().get_text().unwrap(), "1");
+        assert!(form.get_field("file-id").unwrap().get_file().is_some());
+        assert!(form.get_field("unknown").is_none());
+        let mut names: Vec<&str> = form.iter_fields().map(|(name, _)| name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["file-id", "id"]);
+    }
+
+    #[test]
+    fn insert_json_field() {
+        let mut form = Form::new();
+        form.insert_json_field("text", serde_json::json!("text"));
+        form.insert_json_field("object", serde_json::json!({"key": "value"}));
+        form.insert_json_field("null", JsonValue::Null);
+        assert_eq!(form.get_field("text").unwrap().get_text().unwrap(), "text");
+        assert_eq!(
+            form.get_field("object").unwrap().get_text().unwrap(),
+            r#"{"key":"value"}"#
+        );
+        assert!(form.get_field("null").is_none());
+    }
+
+    #[test]
+    fn try_clone() {
+        let mut form = Form::new();
+        form.insert_field("id", 1);
+        form.insert_field("file-id", InputFile::file_id("file-id"));
+        form.insert_field("file-url", InputFile::url("url"));
+        let cloned = form.try_clone().unwrap();
+        assert_eq!(cloned.fields["id"].get_text().unwrap(), "1");
+        assert!(cloned.fields["file-id"].get_file().is_some());
+        assert!(cloned.fields["file-url"].get_file().is_some());
+
+        form.insert_field("file-reader", InputFile::from(Cursor::new(b"test")));
+        assert!(form.try_clone().is_none());
+    }
 }
@@ -1,65 +1,303 @@
-use serde::ser::Serialize;
-use serde_json::Error as JsonError;
+use crate::types::{ChatId, Integer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as JsonError, Value as JsonValue};
+use std::time::Duration;
 
 mod form;
 
-pub(crate) use self::form::*;
+pub use self::form::*;
 
-#[doc(hidden)]
+/// An HTTP request to the Bot API
+///
+/// Produced by [`Method::into_request`](crate::methods::Method::into_request)
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use tgbot::{methods::{Method, SendMessage}, RequestMethod};
+///
+/// let request = SendMessage::new(1, "text").into_request();
+/// assert_eq!(request.get_path(), "sendMessage");
+/// assert_eq!(request.get_method(), RequestMethod::Post);
+/// assert_eq!(request.get_body().get_json().unwrap(), json!({"chat_id": 1, "text": "text"}));
+/// ```
 #[derive(Debug)]
 pub struct Request {
     path: String,
     method: RequestMethod,
     body: RequestBody,
+    extra_timeout: Duration,
 }
 
 impl Request {
-    pub(crate) fn form<P: Into<String>>(path: P, form: Form) -> Self {
+    /// Creates a POST request with a multipart body
+    ///
+    /// Use it for methods which upload files
+    ///
+    /// # Arguments
+    ///
+    /// * path - Name of the API method
+    /// * form - Parameters of the method
+    pub fn form<P: Into<String>>(path: P, form: Form) -> Self {
         Self {
             path: path.into(),
             method: RequestMethod::Post,
             body: RequestBody::Form(form),
+            extra_timeout: Duration::default(),
         }
     }
 
-    pub(crate) fn json<P: Into<String>>(path: P, data: impl Serialize) -> Self {
+    /// Creates a POST request with a JSON body
+    ///
+    /// # Arguments
+    ///
+    /// * path - Name of the API method
+    /// * data - Parameters of the method
+    pub fn json<P: Into<String>>(path: P, data: impl Serialize) -> Self {
         Self {
             path: path.into(),
             method: RequestMethod::Post,
             body: RequestBody::Json(serde_json::to_string(&data)),
+            extra_timeout: Duration::default(),
         }
     }
 
-    pub(crate) fn empty<P: Into<String>>(path: P) -> Self {
+    /// Creates a GET request without body
+    ///
+    /// # Arguments
+    ///
+    /// * path - Name of the API method
+    pub fn empty<P: Into<String>>(path: P) -> Self {
         Self {
             path: path.into(),
             method: RequestMethod::Get,
             body: RequestBody::Empty,
+            extra_timeout: Duration::default(),
         }
     }
 
-    pub(crate) fn build_url(&self, base_url: &str, token: &str) -> String {
+    /// Returns a URL of the request
+    ///
+    /// # Arguments
+    ///
+    /// * base_url - API host
+    /// * token - Bot token
+    pub fn build_url(&self, base_url: &str, token: &str) -> String {
         format!("{}/bot{}/{}", base_url, token, self.path)
     }
 
-    pub(crate) fn get_method(&self) -> RequestMethod {
+    /// Returns a name of the API method
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns an HTTP method
+    pub fn get_method(&self) -> RequestMethod {
         self.method
     }
 
-    pub(crate) fn into_body(self) -> RequestBody {
+    /// Sets a time the server is expected to hold the request
+    ///
+    /// It is added to a request timeout, see [`Config::request_timeout`](crate::Config::request_timeout)
+    pub fn extra_timeout(mut self, extra_timeout: Duration) -> Self {
+        self.extra_timeout = extra_timeout;
+        self
+    }
+
+    pub(crate) fn get_extra_timeout(&self) -> Duration {
+        self.extra_timeout
+    }
+
+    /// Returns a reference to the body of the request
+    pub fn get_body(&self) -> &RequestBody {
+        &self.body
+    }
+
+    /// Returns a body of the request
+    pub fn into_body(self) -> RequestBody {
         self.body
     }
+
+    /// Returns a value of `chat_id` parameter
+    pub(crate) fn get_chat_id(&self) -> Option<ChatId> {
+        match self.body {
+            RequestBody::Form(ref form) => form.fields.get("chat_id").and_then(|x| x.get_text()).map(|x| {
+                x.parse::<Integer>()
+                    .map(ChatId::from)
+                    .unwrap_or_else(|_| ChatId::from(x))
+            }),
+            RequestBody::Json(Ok(ref data)) => serde_json::from_str::<ChatIdParameter>(data)
+                .ok()
+                .and_then(|x| x.chat_id),
+            _ => None,
+        }
+    }
+
+    /// Replaces a value of `chat_id` parameter if it exists
+    pub(crate) fn set_chat_id(&mut self, chat_id: ChatId) {
+        match self.body {
+            RequestBody::Form(ref mut form) if form.fields.contains_key("chat_id") => {
+                form.insert_field("chat_id", chat_id);
+            }
+            RequestBody::Json(Ok(ref data)) => {
+                if let Ok(JsonValue::Object(mut params)) = serde_json::from_str(data) {
+                    if let Some(value) = params.get_mut("chat_id") {
+                        *value = match chat_id {
+                            ChatId::Id(id) => JsonValue::from(id),
+                            ChatId::Username(username) => JsonValue::from(username),
+                        };
+                        self.body = RequestBody::Json(serde_json::to_string(&params));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns a copy of the request if it can be sent once again
+    ///
+    /// Requests containing a file reader or a body which failed to serialize can not be copied
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        Some(Self {
+            path: self.path.clone(),
+            method: self.method,
+            body: match self.body {
+                RequestBody::Form(ref form) => RequestBody::Form(form.try_clone()?),
+                RequestBody::Json(Ok(ref data)) => RequestBody::Json(Ok(data.clone())),
+                RequestBody::Json(Err(_)) => return None,
+                RequestBody::Empty => RequestBody::Empty,
+            },
+            extra_timeout: self.extra_timeout,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatIdParameter {
+    chat_id: Option<ChatId>,
 }
 
+/// HTTP method of a request
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-pub(crate) enum RequestMethod {
+pub enum RequestMethod {
+    /// GET
     Get,
+    /// POST
     Post,
 }
 
+/// Body of a request
 #[derive(Debug)]
-pub(crate) enum RequestBody {
+pub enum RequestBody {
+    /// Multipart form
     Form(Form),
+    /// Serialized JSON
     Json(Result<String, JsonError>),
+    /// No body
     Empty,
 }
+
+impl RequestBody {
+    /// Returns a multipart form
+    pub fn get_form(&self) -> Option<&Form> {
+        match self {
+            RequestBody::Form(form) => Some(form),
+            _ => None,
+        }
+    }
+
+    /// Returns a serialized JSON
+    ///
+    /// `None` is returned when the body is not a JSON or it failed to serialize
+    pub fn get_json_str(&self) -> Option<&str> {
+        match self {
+            RequestBody::Json(Ok(data)) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns a parsed JSON
+    ///
+    /// `None` is returned when the body is not a JSON or it failed to serialize
+    pub fn get_json(&self) -> Option<JsonValue> {
+        self.get_json_str().and_then(|data| serde_json::from_str(data).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_chat_id() {
+        let request = Request::json("sendMessage", json!({"chat_id": 1, "text": "test"}));
+        assert_eq!(request.get_chat_id(), Some(ChatId::Id(1)));
+
+        let request = Request::json("sendMessage", json!({"chat_id": "@username", "text": "test"}));
+        assert_eq!(request.get_chat_id(), Some(ChatId::from("@username")));
+
+        let request = Request::json("getMe", json!({}));
+        assert!(request.get_chat_id().is_none());
+
+        let mut form = Form::new();
+        form.insert_field("chat_id", ChatId::Id(-1));
+        let request = Request::form("sendPhoto", form);
+        assert_eq!(request.get_chat_id(), Some(ChatId::Id(-1)));
+
+        let mut form = Form::new();
+        form.insert_field("chat_id", ChatId::from("@username"));
+        let request = Request::form("sendPhoto", form);
+        assert_eq!(request.get_chat_id(), Some(ChatId::from("@username")));
+
+        assert!(Request::empty("getMe").get_chat_id().is_none());
+    }
+
+    #[test]
+    fn get_body() {
+        let request = Request::json("sendMessage", json!({"chat_id": 1, "text": "test"}));
+        let body = request.get_body();
+        assert_eq!(body.get_json_str().unwrap(), r#"{"chat_id":1,"text":"test"}"#);
+        assert_eq!(body.get_json().unwrap(), json!({"chat_id": 1, "text": "test"}));
+        assert!(body.get_form().is_none());
+
+        let mut form = Form::new();
+        form.insert_field("chat_id", 1);
+        let request = Request::form("sendPhoto", form);
+        let body = request.get_body();
+        assert!(body.get_json_str().is_none());
+        assert!(body.get_json().is_none());
+        let form = body.get_form().unwrap();
+        assert_eq!(form.get_field("chat_id").unwrap().get_text().unwrap(), "1");
+
+        let request = Request::empty("getMe");
+        let body = request.get_body();
+        assert!(body.get_json().is_none());
+        assert!(body.get_form().is_none());
+    }
+
+    #[test]
+    fn set_chat_id() {
+        let mut request = Request::json("sendMessage", json!({"chat_id": 1, "text": "test"}));
+        request.set_chat_id(ChatId::Id(-100));
+        assert_eq!(request.get_chat_id(), Some(ChatId::Id(-100)));
+        match request.into_body() {
+            RequestBody::Json(data) => {
+                let data: JsonValue = serde_json::from_str(&data.unwrap()).unwrap();
+                assert_eq!(data, json!({"chat_id": -100, "text": "test"}));
+            }
+            data => panic!("Unexpected request data: {:?}", data),
+        }
+
+        let mut request = Request::json("getMe", json!({}));
+        request.set_chat_id(ChatId::Id(-100));
+        assert!(request.get_chat_id().is_none());
+
+        let mut form = Form::new();
+        form.insert_field("chat_id", ChatId::Id(1));
+        let mut request = Request::form("sendPhoto", form);
+        request.set_chat_id(ChatId::Id(-100));
+        assert_eq!(request.get_chat_id(), Some(ChatId::Id(-100)));
+    }
+}
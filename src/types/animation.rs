@@ -1,4 +1,4 @@
-use crate::types::{photo_size::PhotoSize, primitive::Integer};
+use crate::types::{file::FileReference, photo_size::PhotoSize, primitive::Integer};
 use serde::Deserialize;
 
 /// An animation file (GIF or H.264/MPEG-4 AVC video without sound)
@@ -27,6 +27,16 @@ pub struct Animation {
     pub file_size: Option<Integer>,
 }
 
+impl FileReference for Animation {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
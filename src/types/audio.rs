@@ -1,4 +1,4 @@
-use crate::types::{photo_size::PhotoSize, primitive::Integer};
+use crate::types::{file::FileReference, photo_size::PhotoSize, primitive::Integer};
 use serde::Deserialize;
 
 /// Audio file to be treated as music by the Telegram clients
@@ -27,6 +27,16 @@ pub struct Audio {
     pub thumb: Option<PhotoSize>,
 }
 
+impl FileReference for Audio {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
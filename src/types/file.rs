@@ -13,7 +13,7 @@ use tokio_util::codec::{BytesCodec, FramedRead};
 /// The file can be downloaded via the link `https://api.telegram.org/file/bot<token>/<file_path>`
 /// It is guaranteed that the link will be valid for at least 1 hour
 /// When the link expires, a new one can be requested by calling getFile
-/// Maximum file size to download is 20 MB
+/// Maximum file size to download is 20 MB, there is no limit when using a local Bot API server
 #[derive(Clone, Debug, Deserialize)]
 pub struct File {
     /// Identifier for this file, which can be used to download or reuse the file
@@ -27,9 +27,55 @@ pub struct File {
     pub file_size: Option<Integer>,
     /// File path
     /// Use `https://api.telegram.org/file/bot<token>/<file_path>` to get the file
+    ///
+    /// Local Bot API server returns an absolute path in its file system
     pub file_path: Option<String>,
 }
 
+/// An object which refers to a file stored on Telegram servers
+///
+/// See [`Api::download`](crate::Api::download)
+pub trait FileReference {
+    /// Returns an identifier of the file
+    fn get_file_id(&self) -> &str;
+
+    /// Returns a size of the file, if known
+    fn get_file_size(&self) -> Option<Integer> {
+        None
+    }
+
+    /// Returns a path to download the file, if known
+    fn get_file_path(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl FileReference for File {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+
+    fn get_file_path(&self) -> Option<&str> {
+        self.file_path.as_deref()
+    }
+}
+
+impl FileReference for str {
+    fn get_file_id(&self) -> &str {
+        self
+    }
+}
+
+impl FileReference for String {
+    fn get_file_id(&self) -> &str {
+        self
+    }
+}
+
 /// Information about a file for reader
 #[derive(Clone, Debug)]
 pub struct InputFileInfo {
@@ -140,6 +186,16 @@ impl InputFile {
         }
     }
 
+    /// Path to file in file system of a local Bot API server
+    ///
+    /// The server reads a file by itself, so the path must be absolute
+    /// and accessible by the server process
+    pub fn local_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            kind: InputFileKind::Url(format!("file://{}", path.as_ref().display())),
+        }
+    }
+
     /// Path to file in FS (will be uploaded using multipart/form-data)
     pub async fn path(path: impl AsRef<Path>) -> IoResult<Self> {
         let path = path.as_ref();
@@ -230,6 +286,28 @@ mod tests {
         assert!(data.file_path.is_none());
     }
 
+    #[test]
+    fn file_reference() {
+        let data: File = serde_json::from_value(serde_json::json!({
+            "file_id": "id",
+            "file_unique_id": "unique-id",
+            "file_size": 123,
+            "file_path": "path"
+        }))
+        .unwrap();
+        assert_eq!(data.get_file_id(), "id");
+        assert_eq!(data.get_file_size(), Some(123));
+        assert_eq!(data.get_file_path(), Some("path"));
+
+        let data = "id";
+        assert_eq!(data.get_file_id(), "id");
+        assert!(data.get_file_size().is_none());
+        assert!(data.get_file_path().is_none());
+
+        let data = String::from("id");
+        assert_eq!(data.get_file_id(), "id");
+    }
+
     #[tokio::test]
     async fn input_file() {
         let id = InputFile::file_id("file-id");
@@ -239,6 +317,11 @@ mod tests {
             format!("{:?}", url.kind),
             r#"InputFileKind::Url("http://example.com/archive.zip")"#
         );
+        let local_path = InputFile::local_path("/tmp/archive.zip");
+        assert_eq!(
+            format!("{:?}", local_path.kind),
+            r#"InputFileKind::Url("file:///tmp/archive.zip")"#
+        );
         // NOTE: you must be sure that file exists in current working directory (usually it exists)
         // otherwise test will fail
         let path = InputFile::path("LICENSE").await.unwrap();
@@ -1,4 +1,4 @@
-use crate::types::primitive::Integer;
+use crate::types::{file::FileReference, primitive::Integer};
 use serde::Deserialize;
 
 mod element;
@@ -36,6 +36,16 @@ pub struct PassportFile {
     pub file_date: Integer,
 }
 
+impl FileReference for PassportFile {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        Some(self.file_size)
+    }
+}
+
 /// Data required for decrypting and authenticating EncryptedPassportElement
 ///
 /// See the Telegram Passport Documentation for a complete description
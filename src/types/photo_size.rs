@@ -1,4 +1,4 @@
-use crate::types::primitive::Integer;
+use crate::types::{file::FileReference, primitive::Integer};
 use serde::Deserialize;
 
 /// Size of a photo or a file / sticker thumbnail
@@ -19,6 +19,16 @@ pub struct PhotoSize {
     pub file_size: Option<Integer>,
 }
 
+impl FileReference for PhotoSize {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
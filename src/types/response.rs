@@ -65,6 +65,83 @@ impl ResponseError {
     pub fn migrate_to_chat_id(&self) -> Option<Integer> {
         self.migrate_to_chat_id
     }
+
+    /// Returns a kind of the error
+    ///
+    /// Kind is detected using error code, parameters and description
+    pub fn kind(&self) -> ResponseErrorKind {
+        if let Some(retry_after) = self.retry_after {
+            return ResponseErrorKind::TooManyRequests { retry_after };
+        }
+        if let Some(chat_id) = self.migrate_to_chat_id {
+            return ResponseErrorKind::ChatMigrated { chat_id };
+        }
+        let description = self.description.to_lowercase();
+        let contains = |pattern: &str| description.contains(pattern);
+        match self.error_code {
+            Some(401) => ResponseErrorKind::Unauthorized,
+            Some(409) => ResponseErrorKind::Conflict,
+            Some(403) if contains("bot was blocked by the user") => ResponseErrorKind::BotBlocked,
+            Some(403) if contains("bot was kicked") => ResponseErrorKind::BotKicked,
+            Some(403) if contains("user is deactivated") => ResponseErrorKind::UserDeactivated,
+            Some(403) if contains("can't initiate conversation") => ResponseErrorKind::CantInitiateConversation,
+            _ if contains("chat not found") => ResponseErrorKind::ChatNotFound,
+            _ if contains("user not found") => ResponseErrorKind::UserNotFound,
+            _ if contains("message is not modified") => ResponseErrorKind::MessageNotModified,
+            _ if contains("message to edit not found") => ResponseErrorKind::MessageToEditNotFound,
+            _ if contains("message to delete not found") => ResponseErrorKind::MessageToDeleteNotFound,
+            _ if contains("message can't be edited") => ResponseErrorKind::MessageCantBeEdited,
+            _ if contains("message can't be deleted") => ResponseErrorKind::MessageCantBeDeleted,
+            _ if contains("not enough rights") || contains("chat_admin_required") => ResponseErrorKind::NotEnoughRights,
+            _ => ResponseErrorKind::Other,
+        }
+    }
+}
+
+/// Kind of a response error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ResponseErrorKind {
+    /// The bot was blocked by the user
+    BotBlocked,
+    /// The bot was kicked from the group, supergroup or channel
+    BotKicked,
+    /// The bot can not send a message to a user who has not started a conversation with it
+    CantInitiateConversation,
+    /// The group has been migrated to a supergroup with the specified identifier
+    ChatMigrated {
+        /// Identifier of the supergroup
+        chat_id: Integer,
+    },
+    /// Chat not found
+    ChatNotFound,
+    /// Another `getUpdates` request is running or a webhook is set
+    Conflict,
+    /// Message can not be deleted
+    MessageCantBeDeleted,
+    /// Message can not be edited
+    MessageCantBeEdited,
+    /// New content of the message is the same as the current one
+    MessageNotModified,
+    /// Message to delete not found
+    MessageToDeleteNotFound,
+    /// Message to edit not found
+    MessageToEditNotFound,
+    /// The bot has not enough rights in the chat to perform the action
+    NotEnoughRights,
+    /// Flood control exceeded
+    TooManyRequests {
+        /// Number of seconds left to wait before the request can be repeated
+        retry_after: Integer,
+    },
+    /// Bot token is invalid or the bot was logged out
+    Unauthorized,
+    /// User account is deleted
+    UserDeactivated,
+    /// User not found
+    UserNotFound,
+    /// Any other error
+    Other,
 }
 
 impl StdError for ResponseError {}
@@ -165,4 +242,94 @@ mod tests {
             panic!("Unexpected response: {:?}", success);
         }
     }
+
+    fn create_error(error_code: Integer, description: &str) -> ResponseError {
+        ResponseError {
+            description: String::from(description),
+            error_code: Some(error_code),
+            migrate_to_chat_id: None,
+            retry_after: None,
+        }
+    }
+
+    #[test]
+    fn error_kind() {
+        for (error_code, description, kind) in vec![
+            (
+                403,
+                "Forbidden: bot was blocked by the user",
+                ResponseErrorKind::BotBlocked,
+            ),
+            (
+                403,
+                "Forbidden: bot was kicked from the supergroup chat",
+                ResponseErrorKind::BotKicked,
+            ),
+            (
+                403,
+                "Forbidden: bot can't initiate conversation with a user",
+                ResponseErrorKind::CantInitiateConversation,
+            ),
+            (
+                403,
+                "Forbidden: user is deactivated",
+                ResponseErrorKind::UserDeactivated,
+            ),
+            (400, "Bad Request: chat not found", ResponseErrorKind::ChatNotFound),
+            (400, "Bad Request: user not found", ResponseErrorKind::UserNotFound),
+            (
+                400,
+                "Bad Request: message is not modified: specified new message content \
+                 and reply markup are exactly the same as a current content and reply markup of the message",
+                ResponseErrorKind::MessageNotModified,
+            ),
+            (
+                400,
+                "Bad Request: message to edit not found",
+                ResponseErrorKind::MessageToEditNotFound,
+            ),
+            (
+                400,
+                "Bad Request: message to delete not found",
+                ResponseErrorKind::MessageToDeleteNotFound,
+            ),
+            (
+                400,
+                "Bad Request: message can't be edited",
+                ResponseErrorKind::MessageCantBeEdited,
+            ),
+            (
+                400,
+                "Bad Request: message can't be deleted",
+                ResponseErrorKind::MessageCantBeDeleted,
+            ),
+            (
+                400,
+                "Bad Request: not enough rights to send text messages to the chat",
+                ResponseErrorKind::NotEnoughRights,
+            ),
+            (
+                400,
+                "Bad Request: CHAT_ADMIN_REQUIRED",
+                ResponseErrorKind::NotEnoughRights,
+            ),
+            (401, "Unauthorized", ResponseErrorKind::Unauthorized),
+            (
+                409,
+                "Conflict: terminated by other getUpdates request",
+                ResponseErrorKind::Conflict,
+            ),
+            (400, "Bad Request: wrong file identifier", ResponseErrorKind::Other),
+        ] {
+            assert_eq!(create_error(error_code, description).kind(), kind, "{}", description);
+        }
+
+        let mut err = create_error(429, "Too Many Requests: retry after 5");
+        err.retry_after = Some(5);
+        assert_eq!(err.kind(), ResponseErrorKind::TooManyRequests { retry_after: 5 });
+
+        let mut err = create_error(400, "Bad Request: group chat was upgraded to a supergroup chat");
+        err.migrate_to_chat_id = Some(-100);
+        assert_eq!(err.kind(), ResponseErrorKind::ChatMigrated { chat_id: -100 });
+    }
 }
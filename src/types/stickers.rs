@@ -1,5 +1,5 @@
 use crate::types::{
-    file::InputFile,
+    file::{FileReference, InputFile},
     photo_size::PhotoSize,
     primitive::{Float, Integer},
 };
@@ -102,6 +102,16 @@ pub struct Sticker {
     pub is_animated: bool,
 }
 
+impl FileReference for Sticker {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 /// Sticker set
 #[derive(Clone, Debug, Deserialize)]
 pub struct StickerSet {
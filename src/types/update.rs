@@ -8,8 +8,11 @@ use crate::types::{
     primitive::Integer,
     user::User,
 };
-use serde::{Deserialize, Deserializer, Serialize};
-use serde_json::Value as JsonValue;
+use serde::{
+    de::{value::Error as ValueError, Error as _, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer, Serialize,
+};
+use serde_json::{Error as JsonError, Map as JsonMap, Value as JsonValue};
 
 /// Incoming update
 #[derive(Clone, Debug, Deserialize)]
@@ -48,6 +51,24 @@ where
 }
 
 impl Update {
+    /// Parses an update
+    ///
+    /// Unlike `Deserialize` implementation, returns an error
+    /// instead of `UpdateKind::Unknown` when an update of a known kind is invalid
+    pub(crate) fn from_value_strict(value: &JsonValue) -> Result<Self, JsonError> {
+        let update = Self::deserialize(value)?;
+        if let UpdateKind::Unknown(JsonValue::Object(ref fields)) = update.kind {
+            for (name, data) in fields {
+                if get_known_kinds().contains(&name.as_str()) {
+                    let mut kind = JsonMap::new();
+                    kind.insert(name.clone(), data.clone());
+                    UpdateKind::deserialize(JsonValue::Object(kind))?;
+                }
+            }
+        }
+        Ok(update)
+    }
+
     /// Returns a chat ID from update
     pub fn get_chat_id(&self) -> Option<Integer> {
         self.get_message().map(|msg| msg.get_chat_id()).or_else(|| {
@@ -159,6 +180,40 @@ pub enum UpdateKind {
     Unknown(JsonValue),
 }
 
+/// Returns names of update kinds as they are declared in `UpdateKind`
+fn get_known_kinds() -> &'static [&'static str] {
+    /// Captures variant names passed by derived `Deserialize` implementation
+    struct VariantsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de, 'a> Deserializer<'de> for VariantsDeserializer<'a> {
+        type Error = ValueError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(ValueError::custom("expected an enum"))
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            variants: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = variants;
+            Err(ValueError::custom("variants captured"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    let mut variants: &'static [&'static str] = &[];
+    let _ = UpdateKind::deserialize(VariantsDeserializer(&mut variants));
+    variants
+}
+
 /// Information about the current status of a webhook
 #[derive(Clone, Debug, Deserialize)]
 pub struct WebhookInfo {
@@ -785,6 +840,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_value_strict() {
+        let message = serde_json::json!({
+            "message_id": 1,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": {"id": 1, "type": "private", "first_name": "test"},
+            "text": "test"
+        });
+        let update = Update::from_value_strict(&serde_json::json!({"update_id": 1, "message": message})).unwrap();
+        assert!(matches!(update.kind, UpdateKind::Message(_)));
+
+        let update = Update::from_value_strict(&serde_json::json!({"update_id": 2, "new_kind": {}})).unwrap();
+        assert!(matches!(update.kind, UpdateKind::Unknown(_)));
+
+        let data = serde_json::json!({"update_id": 3, "message": {"message_id": 1}});
+        let update: Update = serde_json::from_value(data.clone()).unwrap();
+        assert!(matches!(update.kind, UpdateKind::Unknown(_)));
+        assert!(Update::from_value_strict(&data).is_err());
+
+        let data = serde_json::json!({"update_id": 4, "my_chat_member": {}});
+        assert!(Update::from_value_strict(&data).is_err());
+    }
+
+    #[test]
+    fn known_kinds() {
+        let kinds = get_known_kinds();
+        for kind in &[
+            "message",
+            "pre_checkout_query",
+            "my_chat_member",
+            "chat_member",
+            "unknown",
+        ] {
+            assert!(kinds.contains(kind), "{} is not known", kind);
+        }
+    }
+
     #[test]
     fn deserialize_webhook_info_full() {
         let data: WebhookInfo = serde_json::from_value(serde_json::json!({
@@ -1,4 +1,4 @@
-use crate::types::{photo_size::PhotoSize, primitive::Integer};
+use crate::types::{file::FileReference, photo_size::PhotoSize, primitive::Integer};
 use serde::Deserialize;
 
 /// Video file
@@ -27,6 +27,16 @@ pub struct Video {
     pub file_size: Option<Integer>,
 }
 
+impl FileReference for Video {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
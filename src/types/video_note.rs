@@ -1,4 +1,4 @@
-use crate::types::{photo_size::PhotoSize, primitive::Integer};
+use crate::types::{file::FileReference, photo_size::PhotoSize, primitive::Integer};
 use serde::Deserialize;
 
 /// Video message
@@ -21,6 +21,16 @@ pub struct VideoNote {
     pub file_size: Option<Integer>,
 }
 
+impl FileReference for VideoNote {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
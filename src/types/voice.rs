@@ -1,4 +1,4 @@
-use crate::types::primitive::Integer;
+use crate::types::{file::FileReference, primitive::Integer};
 use serde::Deserialize;
 
 /// Voice note
@@ -19,6 +19,16 @@ pub struct Voice {
     pub file_size: Option<Integer>,
 }
 
+impl FileReference for Voice {
+    fn get_file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    fn get_file_size(&self) -> Option<Integer> {
+        self.file_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
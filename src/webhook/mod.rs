@@ -0,0 +1,453 @@
+use crate::{
+    api::Api,
+    handler::UpdateHandler,
+    methods::Method,
+    request::{Form, FormValue, Request as ApiRequest, RequestBody},
+    types::{InputFileKind, Update},
+};
+use bytes::Buf;
+use futures_util::future::{ok, BoxFuture, Ready};
+use http::Error as HttpError;
+use hyper::{
+    body, server::conn::AddrStream, service::Service, Body, Method as HttpMethod, Request, Response, Server, StatusCode,
+};
+use log::{debug, error, warn};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::{
+    convert::Infallible,
+    error::Error as StdError,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+mod options;
+#[cfg(feature = "webhook-tls")]
+mod tls;
+
+pub use self::options::WebhookOptions;
+#[cfg(feature = "webhook-tls")]
+pub use self::tls::{TlsConfig, TlsConfigError};
+pub use hyper::Error as HyperError;
+
+/// An update handler which can reply to a webhook request with a method
+///
+/// Telegram executes a method passed in the webhook response,
+/// so there is no need to send one more request.
+/// Result of such method is not available.
+pub trait WebhookReplyHandler {
+    /// A future returned by `handle` method
+    type Future: Future<Output = Option<WebhookReply>>;
+
+    /// Handles an update
+    ///
+    /// # Arguments
+    ///
+    /// * update - A received update
+    fn handle(&self, update: Update) -> Self::Future;
+}
+
+/// A method to execute in reply to a webhook request
+///
+/// Methods which upload files or have parameters other than an object
+/// can not be passed in the webhook response, they are executed using [`Api`] instead.
+#[derive(Debug)]
+pub struct WebhookReply {
+    request: ApiRequest,
+}
+
+impl WebhookReply {
+    /// Creates a new reply
+    ///
+    /// # Arguments
+    ///
+    /// * method - A method to execute
+    pub fn new<M: Method>(method: M) -> Self {
+        Self {
+            request: method.into_request(),
+        }
+    }
+}
+
+type Handler = dyn Fn(Update) -> BoxFuture<'static, Option<WebhookReply>> + Send + Sync;
+
+#[doc(hidden)]
+pub struct WebhookServiceFactory {
+    path: String,
+    handler: Arc<Handler>,
+    api: Option<Api>,
+    options: Arc<WebhookOptions>,
+}
+
+impl WebhookServiceFactory {
+    #[doc(hidden)]
+    pub fn new<P, H>(path: P, update_handler: H) -> Self
+    where
+        P: Into<String>,
+        H: UpdateHandler + Send + Sync + 'static,
+        H::Future: Send,
+    {
+        let update_handler = Arc::new(update_handler);
+        WebhookServiceFactory {
+            path: path.into(),
+            handler: Arc::new(move |update| {
+                let update_handler = update_handler.clone();
+                Box::pin(async move {
+                    update_handler.handle(update).await;
+                    None
+                })
+            }),
+            api: None,
+            options: Default::default(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn with_reply<P, H>(path: P, api: Api, update_handler: H) -> Self
+    where
+        P: Into<String>,
+        H: WebhookReplyHandler + Send + Sync + 'static,
+        H::Future: Send,
+    {
+        let update_handler = Arc::new(update_handler);
+        WebhookServiceFactory {
+            path: path.into(),
+            handler: Arc::new(move |update| {
+                let update_handler = update_handler.clone();
+                Box::pin(async move { update_handler.handle(update).await })
+            }),
+            api: Some(api),
+            options: Default::default(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn options(mut self, options: WebhookOptions) -> Self {
+        self.options = Arc::new(options);
+        self
+    }
+}
+
+impl WebhookServiceFactory {
+    fn create_service(&self, remote_addr: SocketAddr) -> WebhookService {
+        WebhookService {
+            path: self.path.clone(),
+            handler: self.handler.clone(),
+            api: self.api.clone(),
+            options: self.options.clone(),
+            remote_addr,
+        }
+    }
+}
+
+impl Service<&AddrStream> for WebhookServiceFactory {
+    type Response = WebhookService;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Ok(()).into()
+    }
+
+    fn call(&mut self, conn: &AddrStream) -> Self::Future {
+        ok(self.create_service(conn.remote_addr()))
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct WebhookService {
+    path: String,
+    handler: Arc<Handler>,
+    api: Option<Api>,
+    options: Arc<WebhookOptions>,
+    remote_addr: SocketAddr,
+}
+
+async fn handle_request(service: WebhookService, request: Request<Body>) -> Result<Response<Body>, WebhookError> {
+    let WebhookService {
+        path,
+        handler,
+        api,
+        options,
+        remote_addr,
+    } = service;
+    if let Err(status) = options.verify(request.headers(), remote_addr.ip()) {
+        warn!("Rejected webhook request from {}: {}", remote_addr, status);
+        return Ok(Response::builder().status(status).body(Body::empty())?);
+    }
+    Ok(if let HttpMethod::POST = *request.method() {
+        if request.uri().path() == path {
+            let data = body::aggregate(request).await?;
+            match serde_json::from_reader(data.reader()) {
+                Ok(update) => match handler(update).await {
+                    Some(reply) => reply_with_method(api, reply).await?,
+                    None => Response::new(Body::empty()),
+                },
+                Err(err) => Response::builder()
+                    .header("Content-Type", "text/plain")
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Body::from(format!("Failed to parse update: {}\n", err)))?,
+            }
+        } else {
+            Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty())?
+        }
+    } else {
+        Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .header("Allow", "POST")
+            .body(Body::empty())?
+    })
+}
+
+async fn reply_with_method(api: Option<Api>, reply: WebhookReply) -> Result<Response<Body>, WebhookError> {
+    let request = reply.request;
+    let params = match request.get_body() {
+        // parameters which are not an object can not be merged with the method name
+        RequestBody::Json(Ok(data)) => serde_json::from_str::<JsonMap<String, JsonValue>>(data).ok(),
+        RequestBody::Json(Err(err)) => {
+            error!("Can not reply with method {}: {}", request.get_path(), err);
+            return Ok(Response::new(Body::empty()));
+        }
+        RequestBody::Empty => Some(JsonMap::new()),
+        RequestBody::Form(form) => get_form_params(form),
+    };
+    match params {
+        Some(mut params) => {
+            debug!("Reply with method {}", request.get_path());
+            params.insert(String::from("method"), JsonValue::from(request.get_path()));
+            let data = serde_json::to_vec(&params).expect("Can not serialize method");
+            Ok(Response::builder()
+                .header("Content-Type", "application/json")
+                .body(Body::from(data))?)
+        }
+        None => {
+            match api {
+                Some(api) => {
+                    debug!("Execute method {} instead of reply", request.get_path());
+                    if let Err(err) = api.execute(RawRequest(request)).await {
+                        error!("Failed to execute method in reply to webhook: {}", err);
+                    }
+                }
+                None => error!("Can not execute method {}: API is not available", request.get_path()),
+            }
+            Ok(Response::new(Body::empty()))
+        }
+    }
+}
+
+/// Returns form fields as method parameters or `None` when the form contains a file to upload
+///
+/// Values are passed as strings, Telegram parses them in the same way as form fields
+fn get_form_params(form: &Form) -> Option<JsonMap<String, JsonValue>> {
+    form.iter_fields()
+        .map(|(name, value)| {
+            let value = match value {
+                FormValue::Text(text) => text.clone(),
+                FormValue::File(file) => match file.kind {
+                    InputFileKind::Id(ref value) | InputFileKind::Url(ref value) => value.clone(),
+                    InputFileKind::Reader(_) => return None,
+                },
+            };
+            Some((String::from(name), JsonValue::String(value)))
+        })
+        .collect()
+}
+
+/// A request returned by a reply handler
+struct RawRequest(ApiRequest);
+
+impl Method for RawRequest {
+    type Response = JsonValue;
+
+    fn into_request(self) -> ApiRequest {
+        self.0
+    }
+}
+
+type ServiceFuture = Pin<Box<dyn Future<Output = Result<Response<Body>, WebhookError>> + Send>>;
+
+impl Service<Request<Body>> for WebhookService {
+    type Response = Response<Body>;
+    type Error = WebhookError;
+    type Future = ServiceFuture;
+
+    fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Ok(()).into()
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let result = handle_request(this, request).await;
+            match result {
+                Ok(rep) => Ok(rep),
+                Err(err) => {
+                    error!("Webhook error: {}", err);
+                    Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(Body::empty())
+                        .map_err(WebhookError::from)
+                }
+            }
+        })
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum WebhookError {
+    Hyper(HyperError),
+    Http(HttpError),
+}
+
+impl StdError for WebhookError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use self::WebhookError::*;
+        Some(match self {
+            Hyper(err) => err,
+            Http(err) => err,
+        })
+    }
+}
+
+/// A server for webhook
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run<H>(handler: H)
+/// # where
+/// #     H: tgbot::UpdateHandler + Send + Sync + 'static,
+/// #     H::Future: Send,
+/// # {
+/// use tgbot::webhook::{WebhookOptions, WebhookServer};
+///
+/// WebhookServer::new("/", handler)
+///     .options(WebhookOptions::default().secret_token("secret").telegram_subnets())
+///     .run(([127, 0, 0, 1], 8080))
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub struct WebhookServer {
+    factory: WebhookServiceFactory,
+    #[cfg(feature = "webhook-tls")]
+    tls: Option<TlsConfig>,
+}
+
+impl WebhookServer {
+    /// Creates a new server
+    ///
+    /// # Arguments
+    ///
+    /// * path - URL path for webhook
+    /// * handler - Updates handler
+    pub fn new<P, H>(path: P, handler: H) -> Self
+    where
+        P: Into<String>,
+        H: UpdateHandler + Send + Sync + 'static,
+        H::Future: Send,
+    {
+        Self {
+            factory: WebhookServiceFactory::new(path, handler),
+            #[cfg(feature = "webhook-tls")]
+            tls: None,
+        }
+    }
+
+    /// Creates a new server which replies with methods returned by a handler
+    ///
+    /// # Arguments
+    ///
+    /// * path - URL path for webhook
+    /// * api - API to execute methods which can not be passed in the response
+    /// * handler - Updates handler
+    pub fn with_reply<P, H>(path: P, api: Api, handler: H) -> Self
+    where
+        P: Into<String>,
+        H: WebhookReplyHandler + Send + Sync + 'static,
+        H::Future: Send,
+    {
+        Self {
+            factory: WebhookServiceFactory::with_reply(path, api, handler),
+            #[cfg(feature = "webhook-tls")]
+            tls: None,
+        }
+    }
+
+    /// Set server options
+    pub fn options(mut self, options: WebhookOptions) -> Self {
+        self.factory = self.factory.options(options);
+        self
+    }
+
+    /// Accept HTTPS connections using the given TLS configuration
+    ///
+    /// Plain HTTP connections are accepted when not set.
+    #[cfg(feature = "webhook-tls")]
+    pub fn tls(mut self, config: TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Starts the server
+    ///
+    /// # Arguments
+    ///
+    /// * address - Bind address
+    pub async fn run<A>(self, address: A) -> Result<(), HyperError>
+    where
+        A: Into<SocketAddr>,
+    {
+        let address = address.into();
+        #[cfg(feature = "webhook-tls")]
+        {
+            if let Some(config) = self.tls {
+                return self::tls::serve(&address, config, self.factory).await;
+            }
+        }
+        Server::bind(&address).serve(self.factory).await
+    }
+}
+
+/// Starts a server for webhook
+///
+/// Use [`WebhookServer`] to set options.
+///
+/// # Arguments
+///
+/// * address - Bind address
+/// * path - URL path for webhook
+/// * handler - Updates handler
+pub async fn run_server<A, P, H>(address: A, path: P, handler: H) -> Result<(), HyperError>
+where
+    A: Into<SocketAddr>,
+    P: Into<String>,
+    H: UpdateHandler + Send + Sync + 'static,
+    H::Future: Send,
+{
+    WebhookServer::new(path, handler).run(address).await
+}
+
+/// Starts a server for webhook which replies with methods returned by a handler
+///
+/// Use [`WebhookServer`] to set options.
+///
+/// # Arguments
+///
+/// * address - Bind address
+/// * path - URL path for webhook
+/// * api - API to execute methods which can not be passed in the response
+/// * handler - Updates handler
+pub async fn run_server_with_reply<A, P, H>(address: A, path: P, api: Api, handler: H) -> Result<(), HyperError>
+where
+    A: Into<SocketAddr>,
+    P: Into<String>,
+    H: WebhookReplyHandler + Send + Sync + 'static,
+    H::Future: Send,
+{
+    WebhookServer::with_reply(path, api, handler).run(address).await
+}
@@ -0,0 +1,288 @@
+use hyper::{header::HeaderMap, StatusCode};
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+};
+
+const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
+/// Subnets used by Telegram to send webhook requests
+const TELEGRAM_SUBNETS: &[(Ipv4Addr, u8)] = &[
+    (Ipv4Addr::new(149, 154, 160, 0), 20),
+    (Ipv4Addr::new(91, 108, 4, 0), 22),
+];
+
+/// Options for webhook server
+///
+/// By default all requests are accepted.
+#[derive(Clone, Default)]
+pub struct WebhookOptions {
+    secret_token: Option<String>,
+    allowed_subnets: Vec<IpSubnet>,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl WebhookOptions {
+    /// Accept only requests with the given secret token
+    ///
+    /// Token is passed by Telegram in `X-Telegram-Bot-Api-Secret-Token` header,
+    /// use the same value for [`SetWebhook::secret_token`](crate::methods::SetWebhook::secret_token).
+    /// Requests without the token are rejected with 401 status code.
+    pub fn secret_token<T: Into<String>>(mut self, secret_token: T) -> Self {
+        self.secret_token = Some(secret_token.into());
+        self
+    }
+
+    /// Accept requests only from the given subnet
+    ///
+    /// Requests from other addresses are rejected with 403 status code.
+    ///
+    /// # Arguments
+    ///
+    /// * address - Network address
+    /// * prefix_len - Length of the network prefix in bits
+    pub fn allowed_subnet<A: Into<IpAddr>>(mut self, address: A, prefix_len: u8) -> Self {
+        self.allowed_subnets.push(IpSubnet::new(address.into(), prefix_len));
+        self
+    }
+
+    /// Accept requests only from subnets used by Telegram
+    ///
+    /// See <https://core.telegram.org/bots/webhooks#the-short-version>
+    pub fn telegram_subnets(mut self) -> Self {
+        for (address, prefix_len) in TELEGRAM_SUBNETS {
+            self = self.allowed_subnet(*address, *prefix_len);
+        }
+        self
+    }
+
+    /// Adds an address of a reverse proxy
+    ///
+    /// When a request comes from a trusted proxy,
+    /// a client address is taken from `X-Forwarded-For` header.
+    pub fn trusted_proxy<A: Into<IpAddr>>(mut self, address: A) -> Self {
+        self.trusted_proxies.push(address.into());
+        self
+    }
+
+    /// Returns an error status when the request must be rejected
+    pub(super) fn verify(&self, headers: &HeaderMap, remote_addr: IpAddr) -> Result<(), StatusCode> {
+        if !self.allowed_subnets.is_empty() {
+            let client_addr = self.get_client_addr(headers, remote_addr);
+            if !self.allowed_subnets.iter().any(|subnet| subnet.contains(client_addr)) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+        }
+        if let Some(ref secret_token) = self.secret_token {
+            let token = headers
+                .get(SECRET_TOKEN_HEADER)
+                .map(|x| x.as_bytes())
+                .unwrap_or_default();
+            if !constant_time_eq(token, secret_token.as_bytes()) {
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the rightmost address which does not belong to a trusted proxy
+    fn get_client_addr(&self, headers: &HeaderMap, remote_addr: IpAddr) -> IpAddr {
+        let mut client_addr = normalize(remote_addr);
+        if !self.is_trusted_proxy(client_addr) {
+            return client_addr;
+        }
+        let forwarded_for = headers
+            .get_all(FORWARDED_FOR_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for address in forwarded_for.into_iter().rev() {
+            client_addr = match address.trim().parse() {
+                Ok(address) => normalize(address),
+                // invalid address can not be allowed
+                Err(_) => IpAddr::from(Ipv4Addr::UNSPECIFIED),
+            };
+            if !self.is_trusted_proxy(client_addr) {
+                break;
+            }
+        }
+        client_addr
+    }
+
+    fn is_trusted_proxy(&self, address: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|x| normalize(*x) == address)
+    }
+}
+
+impl fmt::Debug for WebhookOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WebhookOptions")
+            .field("secret_token", &self.secret_token.as_ref().map(|_| "..."))
+            .field("allowed_subnets", &self.allowed_subnets)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct IpSubnet {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpSubnet {
+    fn new(address: IpAddr, prefix_len: u8) -> Self {
+        Self {
+            address: normalize(address),
+            prefix_len,
+        }
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        match (self.address, address) {
+            (IpAddr::V4(subnet), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len.min(32)))
+                    .unwrap_or(0);
+                u32::from(subnet) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(subnet), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len.min(128)))
+                    .unwrap_or(0);
+                u128::from(subnet) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Converts IPv4-mapped IPv6 address to IPv4
+fn normalize(address: IpAddr) -> IpAddr {
+    match address {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => address,
+        },
+        address => address,
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+    use std::net::Ipv6Addr;
+
+    fn headers(items: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in items {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn subnet() {
+        let subnet = IpSubnet::new(Ipv4Addr::new(149, 154, 160, 0).into(), 20);
+        assert!(subnet.contains(Ipv4Addr::new(149, 154, 160, 1).into()));
+        assert!(subnet.contains(Ipv4Addr::new(149, 154, 175, 255).into()));
+        assert!(!subnet.contains(Ipv4Addr::new(149, 154, 176, 0).into()));
+        assert!(!subnet.contains(Ipv6Addr::LOCALHOST.into()));
+
+        let subnet = IpSubnet::new(Ipv4Addr::UNSPECIFIED.into(), 0);
+        assert!(subnet.contains(Ipv4Addr::new(1, 2, 3, 4).into()));
+
+        let subnet = IpSubnet::new(Ipv6Addr::LOCALHOST.into(), 128);
+        assert!(subnet.contains(Ipv6Addr::LOCALHOST.into()));
+        assert!(!subnet.contains(Ipv6Addr::UNSPECIFIED.into()));
+    }
+
+    #[test]
+    fn verify_secret_token() {
+        let options = WebhookOptions::default();
+        let remote_addr = Ipv4Addr::LOCALHOST.into();
+        assert!(options.verify(&HeaderMap::new(), remote_addr).is_ok());
+
+        let options = options.secret_token("secret");
+        assert!(options
+            .verify(&headers(&[(SECRET_TOKEN_HEADER, "secret")]), remote_addr)
+            .is_ok());
+        assert_eq!(
+            options.verify(&headers(&[(SECRET_TOKEN_HEADER, "wrong")]), remote_addr),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            options.verify(&HeaderMap::new(), remote_addr),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(format!("{:?}", options).contains(r#"secret_token: Some("...")"#));
+    }
+
+    #[test]
+    fn verify_address() {
+        let telegram_addr = IpAddr::from(Ipv4Addr::new(149, 154, 167, 1));
+        let proxy_addr = IpAddr::from(Ipv4Addr::new(10, 0, 0, 1));
+        let options = WebhookOptions::default().telegram_subnets();
+        assert!(options.verify(&HeaderMap::new(), telegram_addr).is_ok());
+        assert!(options
+            .verify(&HeaderMap::new(), Ipv4Addr::new(91, 108, 6, 1).into())
+            .is_ok());
+        assert!(options
+            .verify(
+                &HeaderMap::new(),
+                Ipv4Addr::new(149, 154, 167, 1).to_ipv6_mapped().into()
+            )
+            .is_ok());
+        assert_eq!(
+            options.verify(&HeaderMap::new(), proxy_addr),
+            Err(StatusCode::FORBIDDEN)
+        );
+        // header is ignored when a request does not come from a trusted proxy
+        assert_eq!(
+            options.verify(&headers(&[(FORWARDED_FOR_HEADER, "149.154.167.1")]), proxy_addr),
+            Err(StatusCode::FORBIDDEN)
+        );
+
+        let options = options.trusted_proxy(proxy_addr);
+        assert!(options
+            .verify(&headers(&[(FORWARDED_FOR_HEADER, "149.154.167.1")]), proxy_addr)
+            .is_ok());
+        assert!(options
+            .verify(
+                &headers(&[(FORWARDED_FOR_HEADER, "1.2.3.4, 149.154.167.1, 10.0.0.1")]),
+                proxy_addr
+            )
+            .is_ok());
+        assert!(options
+            .verify(
+                &headers(&[
+                    (FORWARDED_FOR_HEADER, "1.2.3.4"),
+                    (FORWARDED_FOR_HEADER, "149.154.167.1")
+                ]),
+                proxy_addr
+            )
+            .is_ok());
+        assert_eq!(
+            options.verify(
+                &headers(&[(FORWARDED_FOR_HEADER, "149.154.167.1, 1.2.3.4")]),
+                proxy_addr
+            ),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            options.verify(&headers(&[(FORWARDED_FOR_HEADER, "invalid")]), proxy_addr),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            options.verify(&HeaderMap::new(), proxy_addr),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+}
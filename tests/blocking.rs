@@ -0,0 +1,27 @@
+#![cfg(feature = "blocking")]
+
+use dotenv::dotenv;
+use mockito::{mock, server_url, Matcher};
+use serde_json::json;
+use std::io::Cursor;
+use tgbot::{blocking::Api, methods::SetChatPhoto, types::InputFile, Config};
+
+#[test]
+fn blocking() {
+    dotenv().ok();
+    env_logger::init();
+    let m = mock("POST", "/bottoken/setChatPhoto")
+        .match_body(Matcher::Regex(String::from("photo-data")))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": true})).unwrap())
+        .create();
+    let api = Api::new(Config::new("token").host(server_url())).unwrap();
+    let photo = InputFile::reader(Cursor::new(b"photo-data"));
+    assert!(api.execute(SetChatPhoto::new(1, photo)).unwrap());
+    m.assert();
+
+    let m = mock("GET", "/file/bottoken/file-path").with_body(b"file-data").create();
+    let mut buf = Vec::new();
+    assert_eq!(api.download_file("file-path", &mut buf).unwrap(), 9);
+    assert_eq!(buf, b"file-data");
+    m.assert();
+}
@@ -0,0 +1,41 @@
+use dotenv::dotenv;
+use mockito::{mock, server_url};
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client,
+};
+use serde_json::json;
+use std::time::Duration;
+use tgbot::{methods::Close, Api, Config};
+
+#[tokio::test]
+async fn client() {
+    dotenv().ok();
+    env_logger::init();
+    let body = serde_json::to_vec(&json!({"ok": true, "result": true})).unwrap();
+
+    let m = mock("GET", "/bottoken/close")
+        .match_header("user-agent", "tgbot-test")
+        .with_body(&body)
+        .create();
+    let config = Config::new("token")
+        .host(server_url())
+        .user_agent("tgbot-test")
+        .pool_idle_timeout(Duration::from_secs(10))
+        .pool_max_idle_per_host(1)
+        .tcp_keepalive(Duration::from_secs(10));
+    let api = Api::new(config).unwrap();
+    assert!(api.execute(Close).await.unwrap());
+    m.assert();
+
+    let m = mock("GET", "/bottoken/close")
+        .match_header("x-custom", "value")
+        .with_body(&body)
+        .create();
+    let mut headers = HeaderMap::new();
+    headers.insert("x-custom", HeaderValue::from_static("value"));
+    let client = Client::builder().default_headers(headers).build().unwrap();
+    let api = Api::new(Config::new("token").host(server_url()).client(client)).unwrap();
+    assert!(api.execute(Close).await.unwrap());
+    m.assert();
+}
@@ -0,0 +1,44 @@
+use dotenv::dotenv;
+use mockito::{mock, server_url};
+use serde_json::json;
+use tgbot::{types::File, Api, Config};
+
+#[tokio::test]
+async fn download() {
+    dotenv().ok();
+    env_logger::init();
+    let expired = mock("GET", "/file/bottoken/old-path")
+        .with_status(404)
+        .with_body("Not Found")
+        .create();
+    let get_file = mock("POST", "/bottoken/getFile")
+        .match_body(r#"{"file_id":"file-id"}"#)
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": true,
+                "result": {
+                    "file_id": "file-id",
+                    "file_unique_id": "file-unique-id",
+                    "file_size": 9,
+                    "file_path": "new-path"
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+    let data = mock("GET", "/file/bottoken/new-path").with_body(b"file-data").create();
+    let file: File = serde_json::from_value(json!({
+        "file_id": "file-id",
+        "file_unique_id": "file-unique-id",
+        "file_size": 9,
+        "file_path": "old-path"
+    }))
+    .unwrap();
+    let api = Api::new(Config::new("token").host(server_url())).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(api.download(&file, &mut buf).await.unwrap(), 9);
+    assert_eq!(buf, b"file-data");
+    expired.assert();
+    get_file.assert();
+    data.assert();
+}
@@ -0,0 +1,45 @@
+use dotenv::dotenv;
+use mockito::{mock, server_url};
+use serde_json::json;
+use std::time::Duration;
+use tgbot::{methods::GetMe, Api, Config, ExecuteError, RetryPolicy};
+
+#[tokio::test]
+async fn retry() {
+    dotenv().ok();
+    env_logger::init();
+    let m = mock("GET", "/bottoken/getMe")
+        .with_status(500)
+        .with_body(
+            serde_json::to_vec(&json!({
+                "ok": false,
+                "error_code": 500,
+                "description": "Internal Server Error"
+            }))
+            .unwrap(),
+        )
+        .expect(3)
+        .create();
+    let policy = RetryPolicy::new(3)
+        .initial_backoff(Duration::from_millis(10))
+        .max_backoff(Duration::from_millis(20));
+    let api = Api::new(Config::new("token").host(server_url()).retry_policy(policy)).unwrap();
+    let err = api.execute(GetMe).await.unwrap_err();
+    assert!(matches!(err, ExecuteError::Response(_)));
+    m.assert();
+
+    let m = mock("GET", "/bottoken/getMe")
+        .with_status(502)
+        .with_body("Bad Gateway")
+        .expect(2)
+        .create();
+    let policy = RetryPolicy::new(2).initial_backoff(Duration::from_millis(10));
+    let api = Api::new(Config::new("token").host(server_url()).retry_policy(policy)).unwrap();
+    let err = api.execute(GetMe).await.unwrap_err();
+    if let ExecuteError::Reqwest(err) = err {
+        assert_eq!(err.status().unwrap().as_u16(), 502);
+    } else {
+        panic!("Unexpected error: {:?}", err);
+    }
+    m.assert();
+}
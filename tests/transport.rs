@@ -0,0 +1,72 @@
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tgbot::{
+    methods::{GetMe, SendMessage},
+    types::ResponseErrorKind,
+    Api, ExecuteError, Request, RequestBody, Transport,
+};
+
+type Requests = Arc<Mutex<Vec<(String, Option<Value>)>>>;
+
+#[derive(Clone, Default)]
+struct MemoryTransport {
+    requests: Requests,
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Value, ExecuteError>> {
+        Box::pin(async move {
+            let path = request.get_path().to_string();
+            let body = match request.into_body() {
+                RequestBody::Json(data) => Some(serde_json::from_str(&data?)?),
+                _ => None,
+            };
+            self.requests.lock().unwrap().push((path.clone(), body));
+            Ok(match path.as_str() {
+                "getMe" => json!({
+                    "ok": true,
+                    "result": {
+                        "id": 1,
+                        "is_bot": true,
+                        "first_name": "test",
+                        "username": "test_bot",
+                        "can_join_groups": true,
+                        "can_read_all_group_messages": false,
+                        "supports_inline_queries": false
+                    }
+                }),
+                _ => json!({
+                    "ok": false,
+                    "error_code": 400,
+                    "description": "Bad Request: chat not found"
+                }),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn transport() {
+    let transport = MemoryTransport::default();
+    let api = Api::new("token").unwrap().with_transport(transport.clone());
+
+    let me = api.execute(GetMe).await.unwrap();
+    assert_eq!(me.username, "test_bot");
+
+    let err = api.execute(SendMessage::new(1, "text")).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::ChatNotFound));
+    if let ExecuteError::Response(err) = err {
+        assert_eq!(err.error_code(), Some(400));
+    } else {
+        panic!("Unexpected error: {:?}", err);
+    }
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0], (String::from("getMe"), None));
+    assert_eq!(
+        requests[1],
+        (String::from("sendMessage"), Some(json!({"chat_id": 1, "text": "text"})))
+    );
+}
@@ -0,0 +1,114 @@
+use dotenv::dotenv;
+use futures_util::future::BoxFuture;
+use hyper::{body, header::HeaderValue, Body, Client, Method, Request, Server, StatusCode};
+use mockito::{mock, server_url, Matcher};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use tgbot::{
+    methods::{RawMethod, SendMessage, SendSticker, SetChatPhoto},
+    types::{InputFile, Update},
+    webhook::{WebhookReply, WebhookReplyHandler, WebhookServiceFactory},
+    Api, Config,
+};
+
+struct Handler;
+
+impl WebhookReplyHandler for Handler {
+    type Future = BoxFuture<'static, Option<WebhookReply>>;
+
+    fn handle(&self, update: Update) -> Self::Future {
+        Box::pin(async move {
+            let chat_id = update.get_chat_id()?;
+            Some(match update.get_message()?.get_text()?.data.as_str() {
+                "/photo" => WebhookReply::new(SetChatPhoto::new(
+                    chat_id,
+                    InputFile::reader(Cursor::new(b"photo-data")),
+                )),
+                "/start" => WebhookReply::new(SendMessage::new(chat_id, "hello")),
+                "/sticker" => WebhookReply::new(SendSticker::new(chat_id, InputFile::file_id("sticker-id"))),
+                "/raw" => WebhookReply::new(RawMethod::<bool>::new("rawMethod", json!([chat_id]))),
+                _ => return None,
+            })
+        })
+    }
+}
+
+fn create_update(text: &str) -> Value {
+    json!({
+        "update_id": 10000,
+        "message": {
+            "date": 1441645532,
+            "chat": {"id": 1111111, "first_name": "Test", "type": "private"},
+            "message_id": 1365,
+            "from": {"id": 1111111, "first_name": "Test", "is_bot": false},
+            "text": text
+        }
+    })
+}
+
+async fn send_update(
+    client: &Client<hyper::client::HttpConnector>,
+    uri: &hyper::Uri,
+    text: &str,
+) -> (StatusCode, Vec<u8>) {
+    let mut req = Request::new(Body::from(serde_json::to_vec(&create_update(text)).unwrap()));
+    *req.method_mut() = Method::POST;
+    *req.uri_mut() = uri.clone();
+    req.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    let rep = client.request(req).await.unwrap();
+    let status = rep.status();
+    let data = body::to_bytes(rep).await.unwrap();
+    (status, data.to_vec())
+}
+
+#[tokio::test]
+async fn webhook_reply() {
+    dotenv().ok();
+    env_logger::init();
+    let m = mock("POST", "/bottoken/setChatPhoto")
+        .match_body(Matcher::Regex(String::from("photo-data")))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": true})).unwrap())
+        .create();
+    let api = Api::new(Config::new("token").host(server_url())).unwrap();
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(WebhookServiceFactory::with_reply("/", api, Handler));
+    let uri: hyper::Uri = format!("http://{}/", server.local_addr()).parse().unwrap();
+    tokio::spawn(server);
+    let client = Client::new();
+
+    let (status, data) = send_update(&client, &uri, "/start").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<Value>(&data).unwrap(),
+        json!({"method": "sendMessage", "chat_id": 1111111, "text": "hello"})
+    );
+
+    // a form without files to upload is passed in the response
+    let (status, data) = send_update(&client, &uri, "/sticker").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<Value>(&data).unwrap(),
+        json!({"method": "sendSticker", "chat_id": "1111111", "sticker": "sticker-id"})
+    );
+
+    let (status, data) = send_update(&client, &uri, "/photo").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(data.is_empty());
+    m.assert();
+
+    // parameters which are not an object can not be passed in the response
+    let m = mock("POST", "/bottoken/rawMethod")
+        .match_body(Matcher::Json(json!([1111111])))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": true})).unwrap())
+        .create();
+    let (status, data) = send_update(&client, &uri, "/raw").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(data.is_empty());
+    m.assert();
+
+    let (status, data) = send_update(&client, &uri, "unknown").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(data.is_empty());
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "webhook-tls")]
+
+use dotenv::dotenv;
+use futures_util::future::BoxFuture;
+use mockito::{mock, server_url, Matcher};
+use reqwest::{Certificate, Client, StatusCode};
+use serde_json::json;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tgbot::{
+    methods::SetWebhook,
+    types::{InputFile, Update},
+    webhook::{TlsConfig, WebhookServer},
+    Api, Config, UpdateHandler,
+};
+use tokio::time::sleep;
+
+const CERT_PATH: &str = "tests/data/webhook.crt";
+const KEY_PATH: &str = "tests/data/webhook.key";
+
+struct Handler {
+    count: Arc<AtomicUsize>,
+}
+
+impl UpdateHandler for Handler {
+    type Future = BoxFuture<'static, ()>;
+
+    fn handle(&self, _update: Update) -> Self::Future {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async {})
+    }
+}
+
+#[tokio::test]
+async fn webhook_tls() {
+    dotenv().ok();
+    env_logger::init();
+
+    let m = mock("POST", "/bottoken/setWebhook")
+        .match_header("content-type", Matcher::Regex(String::from("multipart/form-data")))
+        .match_body(Matcher::Regex(String::from("BEGIN CERTIFICATE")))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": true})).unwrap())
+        .create();
+    let api = Api::new(Config::new("token").host(server_url())).unwrap();
+    let certificate = InputFile::path(CERT_PATH).await.unwrap();
+    let method = SetWebhook::new("https://127.0.0.1:8443/").certificate(certificate);
+    assert!(api.execute(method).await.unwrap());
+    m.assert();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let config = TlsConfig::from_pem_files(CERT_PATH, KEY_PATH).await.unwrap();
+    let server = WebhookServer::new("/", Handler { count: count.clone() })
+        .tls(config)
+        .run(([127, 0, 0, 1], 8443));
+    tokio::spawn(server);
+    sleep(Duration::from_millis(100)).await;
+
+    let cert = std::fs::read(CERT_PATH).unwrap();
+    let client = Client::builder()
+        .add_root_certificate(Certificate::from_pem(&cert).unwrap())
+        .build()
+        .unwrap();
+    let rep = client
+        .post("https://127.0.0.1:8443/")
+        .header("Content-Type", "application/json")
+        .body(r#"{"update_id": 1, "unknown": {}}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(rep.status(), StatusCode::OK);
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+
+    assert!(Client::new()
+        .post("https://127.0.0.1:8443/")
+        .body(r#"{"update_id": 2, "unknown": {}}"#)
+        .send()
+        .await
+        .is_err());
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
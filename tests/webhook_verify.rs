@@ -0,0 +1,71 @@
+use dotenv::dotenv;
+use futures_util::future::BoxFuture;
+use hyper::{Body, Client, Method, Request, Server, StatusCode, Uri};
+use std::{
+    net::Ipv4Addr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tgbot::{
+    types::Update,
+    webhook::{WebhookOptions, WebhookServiceFactory},
+    UpdateHandler,
+};
+
+struct Handler {
+    count: Arc<AtomicUsize>,
+}
+
+impl UpdateHandler for Handler {
+    type Future = BoxFuture<'static, ()>;
+
+    fn handle(&self, _update: Update) -> Self::Future {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async {})
+    }
+}
+
+async fn send_update(client: &Client<hyper::client::HttpConnector>, uri: &Uri, headers: &[(&str, &str)]) -> StatusCode {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    let req = builder.body(Body::from(r#"{"update_id": 1, "unknown": {}}"#)).unwrap();
+    client.request(req).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn webhook_verify() {
+    dotenv().ok();
+    env_logger::init();
+    let count = Arc::new(AtomicUsize::new(0));
+    let options = WebhookOptions::default()
+        .secret_token("secret")
+        .telegram_subnets()
+        .trusted_proxy(Ipv4Addr::LOCALHOST);
+    let server = Server::bind(&([127, 0, 0, 1], 0).into())
+        .serve(WebhookServiceFactory::new("/", Handler { count: count.clone() }).options(options));
+    let uri: Uri = format!("http://{}/", server.local_addr()).parse().unwrap();
+    tokio::spawn(server);
+    let client = Client::new();
+
+    let telegram = ("X-Forwarded-For", "149.154.167.220");
+    let token = ("X-Telegram-Bot-Api-Secret-Token", "secret");
+    assert_eq!(send_update(&client, &uri, &[telegram, token]).await, StatusCode::OK);
+    assert_eq!(send_update(&client, &uri, &[telegram]).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        send_update(&client, &uri, &[telegram, ("X-Telegram-Bot-Api-Secret-Token", "wrong")]).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(send_update(&client, &uri, &[token]).await, StatusCode::FORBIDDEN);
+    assert_eq!(
+        send_update(&client, &uri, &[("X-Forwarded-For", "1.2.3.4"), token]).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}